@@ -0,0 +1,32 @@
+#![allow(unused_imports)]
+#![allow(unreachable_code)]
+
+extern crate dyon;
+extern crate dyon_to_rust;
+
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use dyon::{Variable, Object, Error};
+use dyon_to_rust::intrinsics::*;
+use dyon_to_rust::*;
+
+fn main() {
+    println(&add_one(&variable(&2.0)));
+    let mut _0: Variable = add_one(&variable(&3.0));
+    println(&double(&from_variable(&_0)));
+    println(&mixed(&variable(&1.0), &2.0));
+    println(&greeting());
+}
+fn add_one(_0: &Variable) -> Variable {
+    return binop::add(&_0, &1.0)
+}
+fn double(_0: &f64) -> f64 {
+    return binop::mul(&_0, &2.0)
+}
+fn mixed(_0: &Variable, _1: &f64) -> Variable {
+    return binop::add(&_0, &_1)
+}
+fn greeting() -> Variable {
+    return variable(&Arc::new(String::from("hi")))
+}
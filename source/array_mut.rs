@@ -0,0 +1,32 @@
+#![allow(unused_imports)]
+#![allow(unreachable_code)]
+
+extern crate dyon;
+extern crate dyon_to_rust;
+
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use dyon::{Variable, Object, Error};
+use dyon_to_rust::intrinsics::*;
+use dyon_to_rust::*;
+
+fn main() {
+    let mut _0: Vec<f64> = vec![1.0, 2.0, 3.0];
+    push(&mut _0, &4.0);
+    println(&_0);
+    println(&pop(&mut _0));
+    insert(&mut _0, &0.0, &5.0);
+    println(&_0);
+    println(&remove(&mut _0, &1.0));
+    swap(&mut _0, &0.0, &1.0);
+    println(&_0);
+    reverse(&mut _0);
+    println(&_0);
+    clear(&mut _0);
+    println(&len(&_0));
+    let mut _1: Vec<Variable> = vec![variable(&1.0), variable(&Arc::new(String::from("hi")))];
+    push(&mut _1, &variable(&2.0));
+    insert(&mut _1, &0.0, &variable(&false));
+    println(&_1);
+}
@@ -7,11 +7,11 @@ extern crate dyon_to_rust;
 use std::sync::Arc;
 use std::collections::HashMap;
 
-use dyon::{Variable, Object};
+use dyon::{Variable, Object, Error};
 use dyon_to_rust::intrinsics::*;
 use dyon_to_rust::*;
 
 fn main() {
-    let mut _0 = 1.0;
+    let mut _0: f64 = 1.0;
     println(&_0);
 }
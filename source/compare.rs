@@ -7,13 +7,13 @@ extern crate dyon_to_rust;
 use std::sync::Arc;
 use std::collections::HashMap;
 
-use dyon::{Variable, Object};
+use dyon::{Variable, Object, Error};
 use dyon_to_rust::intrinsics::*;
 use dyon_to_rust::*;
 
 fn main() {
-    let mut _0 = 0.0;
-    let mut _1 = 1.0;
+    let mut _0: f64 = 0.0;
+    let mut _1: f64 = 1.0;
     println(&compop::less(&_0, &_1));
     println(&compop::less_or_equal(&_0, &_1));
     println(&compop::greater(&_0, &_1));
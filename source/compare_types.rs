@@ -0,0 +1,44 @@
+#![allow(unused_imports)]
+#![allow(unreachable_code)]
+
+extern crate dyon;
+extern crate dyon_to_rust;
+
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use dyon::{Variable, Object, Error};
+use dyon_to_rust::intrinsics::*;
+use dyon_to_rust::*;
+
+fn main() {
+    println(&compop::equal(&Arc::new(String::from("a")), &Arc::new(String::from("b"))));
+    println(&compop::less(&Arc::new(String::from("a")), &Arc::new(String::from("b"))));
+    println(&compop::not_equal(&true, &false));
+    println(&compop::equal(&[1.0, 2.0, 0.0, 0.0], &[1.0, 2.0, 0.0, 0.0]));
+    println(&compop::equal(&vec![1.0, 2.0], &vec![1.0, 2.0]));
+    println(&compop::not_equal(&vec![1.0, 2.0], &vec![1.0, 3.0]));
+    let mut _0: Vec<Variable> = vec![variable(&1.0), variable(&Arc::new(String::from("hi")))];
+    println(&compop::equal(&_0[0], &1.0));
+    println(&compop::not_equal(&_0[1], &Arc::new(String::from("hi"))));
+    let mut _1: Vec<f64> = vec![1.0, 3.0, 2.0];
+    let mut _2 = {
+        let mut _3: Secret<f64, _> = Secret::new_f64(::std::f64::NAN);
+        let mut _4 = None;
+        for _2 in iter(&_1) {
+            let _5 = {
+                _2
+            };
+            if _3.val.is_nan() || _5.value() > _3.value() {
+                _3 = _5.into();
+                _4 = Some(_2);
+            }
+        }
+        if let Some(_2) = _4 {
+            _3.secret.push(_2);
+        }
+        _3
+    };
+    println(&compop::greater(&_2, &2.0));
+    println(&why(&compop::greater(&_2, &2.0)));
+}
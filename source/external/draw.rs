@@ -0,0 +1,18 @@
+#![allow(unused_imports)]
+#![allow(unreachable_code)]
+
+extern crate dyon;
+extern crate dyon_to_rust;
+
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use dyon::{Variable, Object, Error};
+use dyon_to_rust::intrinsics::*;
+use dyon_to_rust::*;
+
+fn main() {
+    let mut _0: [f32; 4] = [1.0, 2.0, 0.0, 0.0];
+    engine::draw(owned(&_0));
+    println(&engine::math::distance(&_0, &[4.0, 6.0, 0.0, 0.0]));
+}
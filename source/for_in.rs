@@ -0,0 +1,78 @@
+#![allow(unused_imports)]
+#![allow(unreachable_code)]
+
+extern crate dyon;
+extern crate dyon_to_rust;
+
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use dyon::{Variable, Object, Error};
+use dyon_to_rust::intrinsics::*;
+use dyon_to_rust::*;
+
+fn main() {
+    let mut _0: Vec<f64> = vec![1.0, 2.0, 3.0];
+    for _1 in iter(&_0) {
+        println(&_1);
+    };
+    println(&{
+        let mut _2: f64 = 0.0;
+        for _1 in iter(&_0) {
+            _2 += {
+                binop::mul(&_1, &2.0)
+            };
+        }
+        _2
+    });
+    println(&{
+        let mut _2: f64 = 1.0;
+        for _1 in iter(&_0) {
+            _2 *= {
+                binop::add(&_1, &1.0)
+            };
+        }
+        _2
+    });
+    println(&{
+        let mut _2 = vec![];
+        for _1 in iter(&_0) {
+            let _4 = {
+                binop::add(&_1, &1.0)
+            };
+            _2.push(_4);
+        }
+        _2
+    });
+    println(&{
+        let mut _2: Secret<f64, _> = Secret::new_f64(::std::f64::NAN);
+        let mut _3 = None;
+        for _1 in iter(&_0) {
+            let _4 = {
+                _1
+            };
+            if _2.val.is_nan() || _4.value() > _2.value() {
+                _2 = _4.into();
+                _3 = Some(_1);
+            }
+        }
+        if let Some(_1) = _3 {
+            _2.secret.push(_1);
+        }
+        _2
+    });
+    let mut _1 = {
+        let mut _2: Secret<bool, _> = Secret::new_bool(false);
+        for _1 in iter(&_0) {
+            _2 |= {
+                compop::greater(&_1, &2.0)
+            };
+            if cond(&_2) {
+                _2.secret.push(_1);
+                break;
+            }
+        }
+        _2
+    };
+    println(&why(&_1));
+}
@@ -0,0 +1,32 @@
+#![allow(unused_imports)]
+#![allow(unreachable_code)]
+
+extern crate dyon;
+extern crate dyon_to_rust;
+
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use dyon::{Variable, Object, Error};
+use dyon_to_rust::intrinsics::*;
+use dyon_to_rust::*;
+
+fn main() {
+    let mut _0: f64 = 2.0;
+    let mut _1: Thread<f64> = {
+        let _1 = owned(&_0);
+        Thread::new(::std::thread::spawn(move || foo(&_1)))
+    };
+    println(&unwrap(&join__thread(&_1)));
+    let mut _2: Thread<f64> = {
+        let _2 = owned(&_0);
+        Thread::new(::std::thread::spawn(move || bar(&variable(&_2))))
+    };
+    println(&unwrap(&join__thread(&_2)));
+}
+fn foo(_0: &f64) -> f64 {
+    return binop::mul(&_0, &2.0)
+}
+fn bar(_0: &Variable) -> f64 {
+    return from_variable(&binop::add(&_0, &1.0))
+}
@@ -7,7 +7,7 @@ extern crate dyon_to_rust;
 use std::sync::Arc;
 use std::collections::HashMap;
 
-use dyon::{Variable, Object};
+use dyon::{Variable, Object, Error};
 use dyon_to_rust::intrinsics::*;
 use dyon_to_rust::*;
 
@@ -30,10 +30,10 @@ fn main() {
         _1
     };
     if cond(&false) {
-        println(&"yes")
+        println(&Arc::new(String::from("yes")))
     } else if cond(&true) {
-        println(&"maybe")
+        println(&Arc::new(String::from("maybe")))
     } else {
-        println(&"no")
+        println(&Arc::new(String::from("no")))
     };
 }
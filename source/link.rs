@@ -0,0 +1,26 @@
+#![allow(unused_imports)]
+#![allow(unreachable_code)]
+
+extern crate dyon;
+extern crate dyon_to_rust;
+
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use dyon::{Variable, Object, Error};
+use dyon_to_rust::intrinsics::*;
+use dyon_to_rust::*;
+
+fn main() {
+    let mut _0: Link = {
+        let mut _0 = Link::new();
+        _0.push(&variable(&Arc::new(String::from("hello ")))).unwrap();
+        _0.push(&variable(&1.0)).unwrap();
+        _0.push(&variable(&Arc::new(String::from(" world")))).unwrap();
+        _0.push(&variable(&true)).unwrap();
+        _0
+    };
+    println(&_0);
+    println(&head(&_0));
+    println(&is_empty(&tail(&_0)));
+}
@@ -7,7 +7,7 @@ extern crate dyon_to_rust;
 use std::sync::Arc;
 use std::collections::HashMap;
 
-use dyon::{Variable, Object};
+use dyon::{Variable, Object, Error};
 use dyon_to_rust::intrinsics::*;
 use dyon_to_rust::*;
 
@@ -17,7 +17,7 @@ fn main() {
     loop {
         if !cond(&true) {break};
         {
-            println(&"hi");
+            println(&Arc::new(String::from("hi")));
             break;
         }
         {
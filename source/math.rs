@@ -0,0 +1,45 @@
+#![allow(unused_imports)]
+#![allow(unreachable_code)]
+
+extern crate dyon;
+extern crate dyon_to_rust;
+
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use dyon::{Variable, Object, Error};
+use dyon_to_rust::intrinsics::*;
+use dyon_to_rust::*;
+
+fn main() {
+    println(&sqrt(&16.0));
+    println(&sin(&0.0));
+    println(&asin(&0.0));
+    println(&cos(&0.0));
+    println(&acos(&1.0));
+    println(&tan(&0.0));
+    println(&atan(&0.0));
+    println(&atan2(&0.0, &1.0));
+    println(&exp(&0.0));
+    println(&ln(&1.0));
+    println(&log2(&8.0));
+    println(&log10(&100.0));
+    println(&round(&2.5));
+    println(&abs(&binop::sub(&0.0, &3.0)));
+    println(&floor(&2.5));
+    println(&ceil(&2.5));
+    println(&is_nan(&0.0));
+    println(&is_nan(&sqrt(&binop::sub(&0.0, &1.0))));
+    println(&tau());
+    println(&min(&vec![3.0, 1.0, 2.0]));
+    println(&max(&vec![3.0, 1.0, 2.0]));
+    println(&min(&vec![]));
+    let mut _0: [f32; 4] = [1.0, 2.0, 3.0, 4.0];
+    println(&x(&_0));
+    println(&y(&_0));
+    println(&z(&_0));
+    println(&w(&_0));
+    println(&s(&_0, &2.0));
+    let mut _1: f64 = random();
+    println(&compop::less(&_1, &1.0));
+}
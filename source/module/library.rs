@@ -0,0 +1,10 @@
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use dyon::{Variable, Object, Error};
+use ::runtime::intrinsics::*;
+use ::runtime::*;
+
+pub fn add(_0: &f64, _1: &f64) -> f64 {
+    return binop::add(&_0, &_1)
+}
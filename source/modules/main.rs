@@ -0,0 +1,27 @@
+#![allow(unused_imports)]
+#![allow(unreachable_code)]
+
+extern crate dyon;
+extern crate dyon_to_rust;
+
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use dyon::{Variable, Object, Error};
+use dyon_to_rust::intrinsics::*;
+use dyon_to_rust::*;
+
+mod lib {
+    use super::*;
+
+    pub fn square(_0: &f64) -> f64 {
+        return binop::mul(&_0, &_0)
+    }
+    pub fn print_square(_0: &f64) {
+        println(&square(&_0));
+    }
+}
+fn main() {
+    lib::print_square(&3.0);
+    println(&lib::square(&2.0));
+}
@@ -0,0 +1,37 @@
+#![allow(unused_imports)]
+#![allow(unreachable_code)]
+
+extern crate dyon;
+extern crate dyon_to_rust;
+
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use dyon::{Variable, Object, Error};
+use dyon_to_rust::intrinsics::*;
+use dyon_to_rust::*;
+
+mod lib {
+    use super::*;
+
+    pub fn square(_0: &f64) -> f64 {
+        return binop::mul(&_0, &_0)
+    }
+    pub fn print_square(_0: &f64) {
+        println(&square(&_0));
+    }
+}
+mod shapes {
+    use super::*;
+
+    pub fn area(_0: &f64, _1: &f64) -> f64 {
+        return binop::mul(&_0, &_1)
+    }
+    pub fn print_area(_0: &f64) {
+        println(&area(&_0, &super::lib::square(&_0)));
+    }
+}
+fn main() {
+    shapes::print_area(&2.0);
+    lib::print_square(&shapes::area(&1.0, &2.0));
+}
@@ -0,0 +1,23 @@
+#![allow(unused_imports)]
+#![allow(unreachable_code)]
+
+extern crate dyon;
+extern crate dyon_to_rust;
+
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use dyon::{Variable, Object, Error};
+use dyon_to_rust::intrinsics::*;
+use dyon_to_rust::*;
+
+fn main() {
+    let mut len_: f64 = 2.0;
+    let mut x_: f64 = binop::add(&len_, &1.0);
+    let mut bar_: Vec<f64> = vec![x_];
+    bar(&mut bar_);
+    println(&bar_);
+}
+fn bar(mut a: &mut Vec<f64>) {
+    push(&mut a, &len(&a));
+}
@@ -0,0 +1,33 @@
+#![allow(unused_imports)]
+#![allow(unreachable_code)]
+
+extern crate dyon;
+extern crate dyon_to_rust;
+
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use dyon::{Variable, Object, Error};
+use dyon_to_rust::intrinsics::*;
+use dyon_to_rust::*;
+
+fn main() {
+    let mut a: f64 = 2.0;
+    foo(&mut a);
+    let mut a_1: bool = true;
+    println(&unop::not(&a_1));
+    let mut i: f64 = 0.0;
+    let _3: f64 = 2.0;
+    loop {
+        if i >= _3 {break};
+        {
+            let mut type_: f64 = binop::add(&i, &1.0);
+            println(&type_);
+        }
+        i += 1.0;
+    };
+}
+fn foo(mut x_: &mut f64) {
+    assign(&mut x_, &3.0);
+    println(&x_);
+}
@@ -0,0 +1,29 @@
+#![allow(unused_imports)]
+#![allow(unreachable_code)]
+
+extern crate dyon;
+extern crate dyon_to_rust;
+
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use dyon::{Variable, Object, Error};
+use dyon_to_rust::intrinsics::*;
+use dyon_to_rust::*;
+
+fn main() {
+    let mut _0: f64 = 2.0;
+    foo_mut_1(&mut _0, &3.0);
+    foo(&_0, &3.0);
+    println(&_0);
+    foo_mut_();
+}
+fn foo_mut_1(mut _0: &mut f64, _1: &f64) {
+    assign(&mut _0, &5.0);
+}
+fn foo(_0: &f64, _1: &f64) {
+    println(&binop::add(&_0, &_1));
+}
+fn foo_mut_() {
+    println(&Arc::new(String::from("not overloaded")));
+}
@@ -0,0 +1,49 @@
+#![allow(unused_imports)]
+#![allow(unreachable_code)]
+
+extern crate dyon;
+extern crate dyon_to_rust;
+
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use dyon::{Variable, Object, Error};
+use dyon_to_rust::intrinsics::*;
+use dyon_to_rust::*;
+
+fn main() {
+    println(&foo(&2.0));
+    println(&foo(&0.0));
+    let mut _0: f64 = unwrap(&foo(&3.0));
+    println(&_0);
+    println(&is_err(&foo(&0.0)));
+    let mut _1: Option<f64> = some(&2.0);
+    println(&_1);
+    println(&is_some(&_1));
+    println(&unwrap_err(&baz(&0.0)));
+    println(&is_err(&half(&0.0)));
+}
+fn foo(_0: &f64) -> Result<f64, Error> {
+    let mut _1: f64 = try_res(&bar(&_0), "In function `foo` (line 15, column 10)")?;
+    return ok(&binop::add(&_1, &1.0))
+}
+fn bar(_0: &f64) -> Result<f64, Error> {
+    if cond(&compop::less(&_0, &1.0)) {
+        return err(&Arc::new(String::from("too small")))
+    };
+    return ok(&_0)
+}
+fn baz(_0: &f64) -> Result<f64, Error> {
+    let mut _1: f64 = try_res(&foo(&_0), "In function `baz` (line 27, column 10)")?;
+    return ok(&binop::mul(&_1, &2.0))
+}
+fn half(_0: &f64) -> Result<f64, Error> {
+    let mut _1: f64 = try_res(&positive(&_0), "In function `half` (line 32, column 10)")?;
+    return ok(&binop::div(&_1, &2.0))
+}
+fn positive(_0: &f64) -> Option<f64> {
+    if cond(&compop::greater(&_0, &0.0)) {
+        return some(&_0)
+    };
+    return none()
+}
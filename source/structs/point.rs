@@ -0,0 +1,52 @@
+#![allow(unused_imports)]
+#![allow(unreachable_code)]
+
+extern crate dyon;
+extern crate dyon_to_rust;
+
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use dyon::{Variable, Object, Error};
+use dyon_to_rust::intrinsics::*;
+use dyon_to_rust::*;
+
+fn main() {
+    let mut _0 = Object0 {x: 1.0, y: 2.0, name: Arc::new(String::from("origin"))};
+    _0.x = 3.0;
+    println(&binop::add(&_0.x, &_0.y));
+    println(&_0);
+    let mut _1 = {
+        let mut _1: HashMap<Arc<String>, Variable> = HashMap::new();
+        _1.insert(Arc::new("x".into()), variable(&1.0));
+        Arc::new(_1)
+    };
+    let mut _2 = _1;
+    println(&_2);
+}
+#[derive(Clone)]
+struct Object0 {
+    x: f64,
+    y: f64,
+    name: Arc<String>,
+}
+impl WriteLn for Object0 {
+    fn write<W: ::std::io::Write>(&self, w: &mut W) -> ::std::io::Result<()> {
+        write!(w, "{{x: ")?;
+        self.x.write(w)?;
+        write!(w, ", y: ")?;
+        self.y.write(w)?;
+        write!(w, ", name: ")?;
+        self.name.write(w)?;
+        write!(w, "}}")
+    }
+}
+impl ToVariable for Object0 {
+    fn to_variable(&self) -> Variable {
+        let mut obj: HashMap<Arc<String>, Variable> = HashMap::new();
+        obj.insert(Arc::new("x".into()), variable(&self.x));
+        obj.insert(Arc::new("y".into()), variable(&self.y));
+        obj.insert(Arc::new("name".into()), variable(&self.name));
+        Variable::Object(Arc::new(obj))
+    }
+}
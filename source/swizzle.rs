@@ -7,12 +7,12 @@ extern crate dyon_to_rust;
 use std::sync::Arc;
 use std::collections::HashMap;
 
-use dyon::{Variable, Object};
+use dyon::{Variable, Object, Error};
 use dyon_to_rust::intrinsics::*;
 use dyon_to_rust::*;
 
 fn main() {
-    let mut _0 = [1.0, 2.0, 0.0, 0.0];
+    let mut _0: [f32; 4] = [1.0, 2.0, 0.0, 0.0];
     println(&{
         let ref _1 = _0;
         [index::vec4_look_up(_1, 1), index::vec4_look_up(_1, 0), 0.0, 0.0]
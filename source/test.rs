@@ -7,12 +7,12 @@ extern crate dyon_to_rust;
 use std::sync::Arc;
 use std::collections::HashMap;
 
-use dyon::{Variable, Object};
+use dyon::{Variable, Object, Error};
 use dyon_to_rust::intrinsics::*;
 use dyon_to_rust::*;
 
 fn main() {
-    let mut _0 = 2.0;
+    let mut _0: f64 = 2.0;
     foo(&mut _0);
     println(&_0);
 }
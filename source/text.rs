@@ -0,0 +1,31 @@
+#![allow(unused_imports)]
+#![allow(unreachable_code)]
+
+extern crate dyon;
+extern crate dyon_to_rust;
+
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use dyon::{Variable, Object, Error};
+use dyon_to_rust::intrinsics::*;
+use dyon_to_rust::*;
+
+fn main() {
+    println(&str(&1.5));
+    println(&str(&[1.0, 2.0, 0.0, 0.0]));
+    println(&str(&vec![1.0, 2.0]));
+    println(&str(&vec![Arc::new(String::from("a")), Arc::new(String::from("b"))]));
+    println(&str(&Arc::new(String::from("hi"))));
+    println(&json_string(&Arc::new(String::from("hi"))));
+    println(&chars(&Arc::new(String::from("hi"))));
+    println(&trim(&Arc::new(String::from("  hi  "))));
+    println(&trim_left(&Arc::new(String::from("  hi"))));
+    println(&trim_right(&Arc::new(String::from("hi  "))));
+    println(&parse_number(&Arc::new(String::from(" 12 "))));
+    println(&parse_number(&Arc::new(String::from("x"))));
+    let mut _0: Arc<String> = binop::add(&binop::add(&Arc::new(String::from("hello")), &Arc::new(String::from(" "))), &Arc::new(String::from("world")));
+    println(&_0);
+    println(&binop::add(&_0, &Arc::new(String::from("!"))));
+    println(&binop::add(&str(&2.0), &str(&3.0)));
+}
@@ -0,0 +1,22 @@
+#![allow(unused_imports)]
+#![allow(unreachable_code)]
+
+extern crate dyon;
+extern crate dyon_to_rust;
+
+use std::sync::Arc;
+use std::collections::HashMap;
+
+use dyon::{Variable, Object, Error};
+use dyon_to_rust::intrinsics::*;
+use dyon_to_rust::*;
+
+fn main() {
+    let mut _0: Arc<String> = greet(&Arc::new(String::from("world")));
+    println(&_0);
+    assign(&mut _0, &Arc::new(String::from("bye")));
+    println(&_0);
+}
+fn greet(_0: &Arc<String>) -> Arc<String> {
+    return binop::add(&Arc::new(String::from("hello ")), &_0)
+}
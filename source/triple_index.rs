@@ -7,12 +7,12 @@ extern crate dyon_to_rust;
 use std::sync::Arc;
 use std::collections::HashMap;
 
-use dyon::{Variable, Object};
+use dyon::{Variable, Object, Error};
 use dyon_to_rust::intrinsics::*;
 use dyon_to_rust::*;
 
 fn main() {
-    let mut _0 = vec![vec![vec![1.0, 2.0], vec![3.0, 4.0]]];
+    let mut _0: Vec<Vec<Vec<f64>>> = vec![vec![vec![1.0, 2.0], vec![3.0, 4.0]]];
     let mut _1: f64 = 0.0;
     let _2: f64 = len(&_0);
     loop {
@@ -7,13 +7,13 @@ extern crate dyon_to_rust;
 use std::sync::Arc;
 use std::collections::HashMap;
 
-use dyon::{Variable, Object};
+use dyon::{Variable, Object, Error};
 use dyon_to_rust::intrinsics::*;
 use dyon_to_rust::*;
 
 fn main() {
-    let mut _0 = 2.0;
+    let mut _0: f64 = 2.0;
     println(&unop::neg(&_0));
-    let mut _1 = true;
+    let mut _1: bool = true;
     println(&unop::not(&_1));
 }
@@ -1,5 +1,7 @@
 //! Assignment helper methods.
 
+use std::sync::Arc;
+
 pub trait SetAssign<T> {
     fn set_assign(&mut self, val: &T);
 }
@@ -18,6 +20,12 @@ impl SetAssign<f64> for f64 {
     }
 }
 
+impl SetAssign<Arc<String>> for Arc<String> {
+    fn set_assign(&mut self, val: &Arc<String>) {
+        *self = val.clone()
+    }
+}
+
 /// Helps converting types when assigning a value.
 pub fn set_assign<T: SetAssign<U>, U>(a: &mut T, b: &U) {
     a.set_assign(b)
@@ -0,0 +1,213 @@
+//! Transpiles Dyon scripts to Rust.
+//!
+//! Run `dyon2rust --help` for usage.
+
+extern crate dyon;
+extern crate dyon_to_rust;
+
+use std::env;
+use std::fs::File;
+use std::io::{self, Write};
+use std::process;
+use std::sync::Arc;
+
+use dyon::{load, Dfn, Lt, Module, Runtime};
+use dyon::ty::Type;
+use dyon_to_rust::{check_module_with_settings, generate_code_with_settings};
+use dyon_to_rust::{ArgConvention, CodeSettings};
+
+const USAGE: &str = "\
+Usage: dyon2rust [options] <file.dyon>...
+
+Loads the Dyon files into one module and writes the generated Rust code.
+
+Options:
+    -o, --output <file>   Write to file instead of stdout
+    --variable-names      Use variable names from the Dyon source
+    --object-structs      Generate Rust structs for objects with static keys
+    --lib                 Generate a module with `pub` functions and no crate attributes
+    --runtime <path>      Path to the runtime crate (default `dyon_to_rust`)
+    --extern <ext>        Call a Rust function for an external function, where <ext> is
+                          `<name>(<types>)[-><type>]=<path>[,ref|value|variable]`,
+                          e.g. `distance(vec4,vec4)->f64=engine::distance`.
+                          Types are `any`, `bool`, `f64`, `str`, `vec4` and `link`.
+    --check               Report unsupported features without generating code
+    -h, --help            Print this message
+";
+
+/// Exit code for invalid command line arguments.
+const EXIT_USAGE: i32 = 2;
+/// Exit code when a script could not be loaded or transpiled.
+const EXIT_FAILURE: i32 = 1;
+
+struct Args {
+    files: Vec<String>,
+    output: Option<String>,
+    settings: CodeSettings,
+    /// External functions to register in the Dyon module before loading.
+    externals: Vec<(Arc<String>, Dfn)>,
+    check: bool,
+}
+
+/// Parses a type of an external function argument or return value.
+fn parse_type(ty: &str) -> Result<Type, String> {
+    Ok(match ty.trim() {
+        "any" => Type::Any,
+        "bool" => Type::Bool,
+        "f64" => Type::F64,
+        "str" => Type::Text,
+        "vec4" => Type::Vec4,
+        "link" => Type::Link,
+        _ => return Err(format!("Unknown type `{}`", ty)),
+    })
+}
+
+/// Parses an external function, e.g. `distance(vec4,vec4)->f64=engine::distance,ref`.
+///
+/// Returns the Dyon signature, the Rust path and the argument convention.
+fn parse_extern(ext: &str) -> Result<(Arc<String>, Dfn, String, ArgConvention), String> {
+    let invalid = || format!("Invalid external function `{}`", ext);
+
+    let eq = ext.find('=').ok_or_else(&invalid)?;
+    let (sig, rust) = (&ext[..eq], &ext[eq + 1..]);
+    let open = sig.find('(').ok_or_else(&invalid)?;
+    let close = sig.find(')').ok_or_else(&invalid)?;
+    if close < open {return Err(invalid())}
+
+    let name = sig[..open].trim();
+    let args = sig[open + 1..close].trim();
+    let tys = if args.is_empty() {
+        vec![]
+    } else {
+        args.split(',').map(parse_type).collect::<Result<Vec<Type>, String>>()?
+    };
+    let ret = sig[close + 1..].trim();
+    let ret = if ret.is_empty() {
+        Type::Void
+    } else if let Some(ty) = ret.strip_prefix("->") {
+        parse_type(ty)?
+    } else {
+        return Err(invalid())
+    };
+
+    let mut parts = rust.split(',');
+    let path = parts.next().unwrap_or("").trim();
+    let convention = match parts.next().map(|c| c.trim()) {
+        None | Some("ref") => ArgConvention::Ref,
+        Some("value") => ArgConvention::Value,
+        Some("variable") => ArgConvention::Variable,
+        Some(c) => return Err(format!("Unknown argument convention `{}`", c)),
+    };
+    if name.is_empty() || path.is_empty() || parts.next().is_some() {
+        return Err(invalid());
+    }
+
+    let dfn = Dfn {
+        lts: tys.iter().map(|_| Lt::Default).collect(),
+        tys: tys,
+        ret: ret,
+    };
+    Ok((Arc::new(name.into()), dfn, path.into(), convention))
+}
+
+/// Stands in for external functions, which are only called by the generated code.
+fn external(_: &mut Runtime) -> Result<(), String> {
+    Err(String::from("External functions can not be called when transpiling"))
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        files: vec![],
+        output: None,
+        settings: CodeSettings::new(),
+        externals: vec![],
+        check: false,
+    };
+    let mut iter = env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        match &arg[..] {
+            "-h" | "--help" => {
+                print!("{}", USAGE);
+                process::exit(0);
+            }
+            "-o" | "--output" => match iter.next() {
+                Some(file) => args.output = Some(file),
+                None => return Err(format!("Missing file after `{}`", arg)),
+            },
+            "--variable-names" => args.settings.variable_names = true,
+            "--object-structs" => args.settings.object_structs = true,
+            "--lib" => {
+                args.settings.preamble = false;
+                args.settings.public = true;
+            }
+            "--runtime" => match iter.next() {
+                Some(path) => args.settings.runtime = path,
+                None => return Err(format!("Missing path after `{}`", arg)),
+            },
+            "--extern" => match iter.next() {
+                Some(ext) => {
+                    let (name, dfn, path, convention) = parse_extern(&ext)?;
+                    args.settings.externals.add(&name, &path, convention);
+                    args.externals.push((name, dfn));
+                }
+                None => return Err(format!("Missing external function after `{}`", arg)),
+            },
+            "--check" => args.check = true,
+            _ if arg.starts_with('-') => return Err(format!("Unknown option `{}`", arg)),
+            _ => args.files.push(arg),
+        }
+    }
+    if args.files.is_empty() {
+        return Err(String::from("No input files"));
+    }
+    Ok(args)
+}
+
+fn main() {
+    let mut args = match parse_args() {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("error: {}\n\n{}", err, USAGE);
+            process::exit(EXIT_USAGE);
+        }
+    };
+
+    let mut module = Module::new();
+    for (name, dfn) in args.externals.drain(..) {
+        module.add(name, external, dfn);
+    }
+    for file in &args.files {
+        if let Err(err) = load(file, &mut module) {
+            eprintln!("{}", err);
+            eprintln!("error: Could not load `{}`", file);
+            process::exit(EXIT_FAILURE);
+        }
+    }
+
+    if args.check {
+        let diagnostics = check_module_with_settings(&module, &args.settings);
+        for diagnostic in &diagnostics {
+            eprintln!("error: {}", diagnostic);
+        }
+        if !diagnostics.is_empty() {
+            process::exit(EXIT_FAILURE);
+        }
+        return;
+    }
+
+    // Generate into memory first, such that no partial output is written on failure.
+    let mut buf: Vec<u8> = vec![];
+    if let Err(err) = generate_code_with_settings(&mut buf, &module, &args.settings) {
+        eprintln!("error: {}", err);
+        process::exit(EXIT_FAILURE);
+    }
+
+    let res = match args.output {
+        Some(ref file) => File::create(file).and_then(|mut f| f.write_all(&buf)),
+        None => io::stdout().write_all(&buf),
+    };
+    if let Err(err) = res {
+        eprintln!("error: Could not write output: {}", err);
+        process::exit(EXIT_FAILURE);
+    }
+}
@@ -1,12 +1,18 @@
 //! Binary operators.
 
-pub trait Add<Rhs = Self> {
+use std::sync::Arc;
+
+use dyon::Variable;
+
+use variable;
+
+pub trait Add<Rhs: ?Sized = Self> {
     type Output;
 
     fn add(&self, other: &Rhs) -> Self::Output;
 }
 
-impl<'a, T: Add> Add for &'a T {
+impl<'a, T: Add + ?Sized> Add for &'a T {
     type Output = T::Output;
 
     fn add(&self, other: &Self) -> T::Output {
@@ -79,6 +85,72 @@ impl Add for bool {
     }
 }
 
+/// Concatenates two strings, like Dyon does when adding text.
+fn concat(a: &str, b: &str) -> Arc<String> {
+    let mut res = String::with_capacity(a.len() + b.len());
+    res.push_str(a);
+    res.push_str(b);
+    Arc::new(res)
+}
+
+impl Add for str {
+    type Output = Arc<String>;
+
+    fn add(&self, other: &str) -> Arc<String> {
+        concat(self, other)
+    }
+}
+
+impl Add for Arc<String> {
+    type Output = Arc<String>;
+
+    fn add(&self, other: &Arc<String>) -> Arc<String> {
+        concat(self, other)
+    }
+}
+
+impl<'a> Add<&'a str> for Arc<String> {
+    type Output = Arc<String>;
+
+    fn add(&self, other: &&'a str) -> Arc<String> {
+        concat(self, other)
+    }
+}
+
+impl<'a> Add<Arc<String>> for &'a str {
+    type Output = Arc<String>;
+
+    fn add(&self, other: &Arc<String>) -> Arc<String> {
+        concat(self, other)
+    }
+}
+
+// Text arguments of functions are references.
+
+impl<'a> Add<&'a Arc<String>> for Arc<String> {
+    type Output = Arc<String>;
+
+    fn add(&self, other: &&'a Arc<String>) -> Arc<String> {
+        concat(self, other)
+    }
+}
+
+impl<'a, 'b> Add<&'b str> for &'a Arc<String> {
+    type Output = Arc<String>;
+
+    fn add(&self, other: &&'b str) -> Arc<String> {
+        concat(self, other)
+    }
+}
+
+impl<'a, 'b> Add<&'b Arc<String>> for &'a str {
+    type Output = Arc<String>;
+
+    fn add(&self, other: &&'b Arc<String>) -> Arc<String> {
+        concat(self, other)
+    }
+}
+
 pub fn add<T: Add<U>, U>(a: &T, b: &U) -> T::Output {
     a.add(b)
 }
@@ -450,3 +522,168 @@ impl Pow<f64> for [f32; 4] {
 pub fn pow<T: Pow<U>, U>(a: &T, b: &U) -> T::Output {
     a.pow(b)
 }
+
+// Binary operators on dynamic Dyon variables, used by code without type annotations.
+// The operator is dispatched at runtime, like Dyon does.
+
+fn invalid_types(op: &str) -> ! {
+    panic!("Invalid types for binary operator `{}`", op)
+}
+
+impl Add for Variable {
+    type Output = Variable;
+
+    fn add(&self, other: &Variable) -> Variable {
+        use dyon::Variable::*;
+
+        match (self, other) {
+            (&F64(a, _), &F64(b, _)) => variable(&a.add(&b)),
+            (&Vec4(a), &Vec4(b)) => variable(&a.add(&b)),
+            (&Vec4(a), &F64(b, _)) => variable(&a.add(&b)),
+            (&F64(a, _), &Vec4(b)) => variable(&a.add(&b)),
+            (&Bool(a, _), &Bool(b, _)) => variable(&a.add(&b)),
+            (&Text(ref a), &Text(ref b)) => Text(concat(a, b)),
+            _ => invalid_types("+"),
+        }
+    }
+}
+
+impl Sub for Variable {
+    type Output = Variable;
+
+    fn sub(&self, other: &Variable) -> Variable {
+        use dyon::Variable::*;
+
+        match (self, other) {
+            (&F64(a, _), &F64(b, _)) => variable(&a.sub(&b)),
+            (&Vec4(a), &Vec4(b)) => variable(&a.sub(&b)),
+            (&Vec4(a), &F64(b, _)) => variable(&a.sub(&b)),
+            (&F64(a, _), &Vec4(b)) => variable(&a.sub(&b)),
+            _ => invalid_types("-"),
+        }
+    }
+}
+
+impl Mul for Variable {
+    type Output = Variable;
+
+    fn mul(&self, other: &Variable) -> Variable {
+        use dyon::Variable::*;
+
+        match (self, other) {
+            (&F64(a, _), &F64(b, _)) => variable(&a.mul(&b)),
+            (&Vec4(a), &Vec4(b)) => variable(&a.mul(&b)),
+            (&Vec4(a), &F64(b, _)) => variable(&a.mul(&b)),
+            (&F64(a, _), &Vec4(b)) => variable(&a.mul(&b)),
+            (&Bool(a, _), &Bool(b, _)) => variable(&a.mul(&b)),
+            _ => invalid_types("*"),
+        }
+    }
+}
+
+impl Div for Variable {
+    type Output = Variable;
+
+    fn div(&self, other: &Variable) -> Variable {
+        use dyon::Variable::*;
+
+        match (self, other) {
+            (&F64(a, _), &F64(b, _)) => variable(&a.div(&b)),
+            (&Vec4(a), &Vec4(b)) => variable(&a.div(&b)),
+            (&Vec4(a), &F64(b, _)) => variable(&a.div(&b)),
+            (&F64(a, _), &Vec4(b)) => variable(&a.div(&b)),
+            _ => invalid_types("/"),
+        }
+    }
+}
+
+impl Rem for Variable {
+    type Output = Variable;
+
+    fn rem(&self, other: &Variable) -> Variable {
+        use dyon::Variable::*;
+
+        match (self, other) {
+            (&F64(a, _), &F64(b, _)) => variable(&a.rem(&b)),
+            (&Vec4(a), &Vec4(b)) => variable(&a.rem(&b)),
+            (&Vec4(a), &F64(b, _)) => variable(&a.rem(&b)),
+            (&F64(a, _), &Vec4(b)) => variable(&a.rem(&b)),
+            _ => invalid_types("%"),
+        }
+    }
+}
+
+impl Pow for Variable {
+    type Output = Variable;
+
+    fn pow(&self, other: &Variable) -> Variable {
+        use dyon::Variable::*;
+
+        match (self, other) {
+            (&F64(a, _), &F64(b, _)) => variable(&a.pow(&b)),
+            (&Vec4(a), &F64(b, _)) => variable(&a.pow(&b)),
+            _ => invalid_types("^"),
+        }
+    }
+}
+
+/// Implements operators between dynamic variables and numbers,
+/// by converting the number into a variable.
+macro_rules! variable_f64 {
+    ($op:ident, $method:ident) => {
+        impl $op<f64> for Variable {
+            type Output = Variable;
+
+            fn $method(&self, other: &f64) -> Variable {
+                self.$method(&Variable::f64(*other))
+            }
+        }
+
+        impl $op<Variable> for f64 {
+            type Output = Variable;
+
+            fn $method(&self, other: &Variable) -> Variable {
+                Variable::f64(*self).$method(other)
+            }
+        }
+
+        impl<'a> $op<f64> for &'a Variable {
+            type Output = Variable;
+
+            fn $method(&self, other: &f64) -> Variable {
+                (*self).$method(other)
+            }
+        }
+
+        impl<'a> $op<Variable> for &'a f64 {
+            type Output = Variable;
+
+            fn $method(&self, other: &Variable) -> Variable {
+                (*self).$method(other)
+            }
+        }
+
+        impl<'a, 'b> $op<&'b f64> for &'a Variable {
+            type Output = Variable;
+
+            fn $method(&self, other: &&'b f64) -> Variable {
+                (*self).$method(*other)
+            }
+        }
+
+        impl<'a, 'b> $op<&'b Variable> for &'a f64 {
+            type Output = Variable;
+
+            fn $method(&self, other: &&'b Variable) -> Variable {
+                (*self).$method(*other)
+            }
+        }
+    }
+}
+
+variable_f64!(Add, add);
+variable_f64!(Sub, sub);
+variable_f64!(Mul, mul);
+variable_f64!(Div, div);
+variable_f64!(Rem, rem);
+variable_f64!(Pow, pow);
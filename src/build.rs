@@ -0,0 +1,147 @@
+//! Transpiles Dyon scripts from a build script.
+//!
+//! Call `transpile_dir` in "build.rs" of your crate:
+//!
+//! ```ignore
+//! extern crate dyon_to_rust;
+//!
+//! fn main() {
+//!     dyon_to_rust::build::transpile_dir("scripts", "scripts").unwrap();
+//! }
+//! ```
+//!
+//! Then include the generated module in your crate:
+//!
+//! ```ignore
+//! extern crate dyon;
+//! extern crate dyon_to_rust;
+//!
+//! include!(concat!(env!("OUT_DIR"), "/scripts.rs"));
+//!
+//! fn main() {
+//!     scripts::main();
+//! }
+//! ```
+//!
+//! All ".dyon" files in the directory are loaded into the same Dyon module.
+//! Cargo runs the build script again when a script changes.
+
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use dyon::{load, Module};
+
+use {generate_code_with_settings, CodeSettings, TranspileError};
+
+/// An error that occured while transpiling from a build script.
+#[derive(Debug)]
+pub enum BuildError {
+    /// The `OUT_DIR` environment variable is not set.
+    ///
+    /// Cargo sets this when running build scripts.
+    MissingOutDir,
+    /// Dyon failed to load a script.
+    Load {
+        /// The script file.
+        file: PathBuf,
+        /// The error message from Dyon.
+        message: String,
+    },
+    /// Failed to generate code.
+    Transpile(TranspileError),
+    /// Failed to read the directory or write the generated code.
+    Io(io::Error),
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BuildError::MissingOutDir => {
+                write!(f, "`OUT_DIR` is not set, transpiling must happen in a build script")
+            }
+            BuildError::Load {ref file, ref message} => {
+                write!(f, "Could not load `{}`:\n{}", file.display(), message)
+            }
+            BuildError::Transpile(ref err) => write!(f, "{}", err),
+            BuildError::Io(ref err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for BuildError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            BuildError::Transpile(ref err) => Some(err),
+            BuildError::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<TranspileError> for BuildError {
+    fn from(err: TranspileError) -> BuildError {
+        BuildError::Transpile(err)
+    }
+}
+
+impl From<io::Error> for BuildError {
+    fn from(err: io::Error) -> BuildError {
+        BuildError::Io(err)
+    }
+}
+
+/// Transpiles all Dyon scripts in a directory into a module in `OUT_DIR`.
+///
+/// Uses `CodeSettings::library()`, such that the generated functions are `pub`.
+/// The generated file is named after the module and returned on success.
+pub fn transpile_dir<P: AsRef<Path>>(dir: P, name: &str) -> Result<PathBuf, BuildError> {
+    transpile_dir_with_settings(dir, name, &CodeSettings::library())
+}
+
+/// Transpiles all Dyon scripts in a directory into a module in `OUT_DIR` using settings.
+pub fn transpile_dir_with_settings<P: AsRef<Path>>(
+    dir: P,
+    name: &str,
+    settings: &CodeSettings
+) -> Result<PathBuf, BuildError> {
+    let out_dir = match env::var_os("OUT_DIR") {
+        Some(out_dir) => PathBuf::from(out_dir),
+        None => return Err(BuildError::MissingOutDir),
+    };
+    let dir = dir.as_ref();
+
+    // Watch the directory too, such that new scripts are picked up.
+    println!("cargo:rerun-if-changed={}", dir.display());
+    let mut files = vec![];
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().map(|ext| ext == "dyon").unwrap_or(false) {
+            files.push(path);
+        }
+    }
+    // Load in the same order every time, since function indices depend on it.
+    files.sort();
+
+    let mut module = Module::new();
+    for file in &files {
+        println!("cargo:rerun-if-changed={}", file.display());
+        let source = file.to_string_lossy();
+        if let Err(message) = load(&source, &mut module) {
+            return Err(BuildError::Load {file: file.clone(), message: message});
+        }
+    }
+
+    let mut buf: Vec<u8> = vec![];
+    writeln!(buf, "#[allow(unused_imports, unreachable_code, dead_code, non_snake_case)]")?;
+    writeln!(buf, "pub mod {} {{", name)?;
+    generate_code_with_settings(&mut buf, &module, settings)?;
+    writeln!(buf, "}}")?;
+
+    let out_file = out_dir.join(format!("{}.rs", name));
+    File::create(&out_file)?.write_all(&buf)?;
+    Ok(out_file)
+}
@@ -1,5 +1,12 @@
 //! Compare operators.
 
+use std::sync::Arc;
+
+use dyon::Variable;
+
+use variable::ToVariable;
+use Secret;
+
 pub trait Less<Rhs = Self> {
     type Output;
 
@@ -14,6 +21,14 @@ impl Less for f64 {
     }
 }
 
+impl Less for Arc<String> {
+    type Output = bool;
+
+    fn less(&self, other: &Arc<String>) -> bool {
+        self < other
+    }
+}
+
 pub fn less<T: Less<U>, U>(a: &T, b: &U) -> T::Output {
     a.less(b)
 }
@@ -32,6 +47,14 @@ impl LessOrEqual for f64 {
     }
 }
 
+impl LessOrEqual for Arc<String> {
+    type Output = bool;
+
+    fn less_or_equal(&self, other: &Arc<String>) -> bool {
+        self <= other
+    }
+}
+
 pub fn less_or_equal<T: LessOrEqual<U>, U>(a: &T, b: &U) -> T::Output {
     a.less_or_equal(b)
 }
@@ -50,6 +73,14 @@ impl Greater for f64 {
     }
 }
 
+impl Greater for Arc<String> {
+    type Output = bool;
+
+    fn greater(&self, other: &Arc<String>) -> bool {
+        self > other
+    }
+}
+
 pub fn greater<T: Greater<U>, U>(a: &T, b: &U) -> T::Output {
     a.greater(b)
 }
@@ -68,6 +99,14 @@ impl GreaterOrEqual for f64 {
     }
 }
 
+impl GreaterOrEqual for Arc<String> {
+    type Output = bool;
+
+    fn greater_or_equal(&self, other: &Arc<String>) -> bool {
+        self >= other
+    }
+}
+
 pub fn greater_or_equal<T: GreaterOrEqual<U>, U>(a: &T, b: &U) -> T::Output {
     a.greater_or_equal(b)
 }
@@ -86,6 +125,39 @@ impl Equal for f64 {
     }
 }
 
+impl Equal for Arc<String> {
+    type Output = bool;
+
+    fn equal(&self, other: &Arc<String>) -> bool {
+        self == other
+    }
+}
+
+impl Equal for bool {
+    type Output = bool;
+
+    fn equal(&self, other: &bool) -> bool {
+        self == other
+    }
+}
+
+impl Equal for [f32; 4] {
+    type Output = bool;
+
+    fn equal(&self, other: &[f32; 4]) -> bool {
+        self == other
+    }
+}
+
+impl<T: Equal<Output = bool>> Equal for Vec<T> {
+    type Output = bool;
+
+    fn equal(&self, other: &Vec<T>) -> bool {
+        self.len() == other.len() &&
+            self.iter().zip(other.iter()).all(|(a, b)| a.equal(b))
+    }
+}
+
 pub fn equal<T: Equal<U>, U>(a: &T, b: &U) -> T::Output {
     a.equal(b)
 }
@@ -104,6 +176,239 @@ impl NotEqual for f64 {
     }
 }
 
+impl NotEqual for Arc<String> {
+    type Output = bool;
+
+    fn not_equal(&self, other: &Arc<String>) -> bool {
+        self != other
+    }
+}
+
+impl NotEqual for bool {
+    type Output = bool;
+
+    fn not_equal(&self, other: &bool) -> bool {
+        self != other
+    }
+}
+
+impl NotEqual for [f32; 4] {
+    type Output = bool;
+
+    fn not_equal(&self, other: &[f32; 4]) -> bool {
+        self != other
+    }
+}
+
+impl<T: Equal<Output = bool>> NotEqual for Vec<T> {
+    type Output = bool;
+
+    fn not_equal(&self, other: &Vec<T>) -> bool {
+        !self.equal(other)
+    }
+}
+
 pub fn not_equal<T: NotEqual<U>, U>(a: &T, b: &U) -> T::Output {
     a.not_equal(b)
 }
+
+/// Implements an operator for references to values,
+/// since arguments are references in generated code.
+macro_rules! compop_ref {
+    ($op:ident, $method:ident) => {
+        impl<'a, T: $op> $op for &'a T {
+            type Output = T::Output;
+
+            fn $method(&self, other: &Self) -> T::Output {
+                (*self).$method(*other)
+            }
+        }
+
+        impl<'a, T: $op> $op<T> for &'a T {
+            type Output = T::Output;
+
+            fn $method(&self, other: &T) -> T::Output {
+                (*self).$method(other)
+            }
+        }
+    }
+}
+
+compop_ref!(Less, less);
+compop_ref!(LessOrEqual, less_or_equal);
+compop_ref!(Greater, greater);
+compop_ref!(GreaterOrEqual, greater_or_equal);
+compop_ref!(Equal, equal);
+compop_ref!(NotEqual, not_equal);
+
+/// Implements an operator for secrets.
+///
+/// Like Dyon, the secret of the left argument is kept in the result,
+/// such that e.g. `why(max i {x[i]} > 2)` returns the index of the maximum.
+macro_rules! compop_secret {
+    ($op:ident, $method:ident, $ty:ty) => {
+        impl<A: Clone> $op for Secret<$ty, A> {
+            type Output = Secret<bool, A>;
+
+            fn $method(&self, other: &Secret<$ty, A>) -> Secret<bool, A> {
+                Secret {val: self.val.$method(&other.val), secret: self.secret.clone()}
+            }
+        }
+
+        impl<A: Clone> $op<$ty> for Secret<$ty, A> {
+            type Output = Secret<bool, A>;
+
+            fn $method(&self, other: &$ty) -> Secret<bool, A> {
+                Secret {val: self.val.$method(other), secret: self.secret.clone()}
+            }
+        }
+
+        impl<A> $op<Secret<$ty, A>> for $ty {
+            type Output = bool;
+
+            fn $method(&self, other: &Secret<$ty, A>) -> bool {
+                self.$method(&other.val)
+            }
+        }
+    }
+}
+
+compop_secret!(Less, less, f64);
+compop_secret!(LessOrEqual, less_or_equal, f64);
+compop_secret!(Greater, greater, f64);
+compop_secret!(GreaterOrEqual, greater_or_equal, f64);
+compop_secret!(Equal, equal, f64);
+compop_secret!(NotEqual, not_equal, f64);
+compop_secret!(Equal, equal, bool);
+compop_secret!(NotEqual, not_equal, bool);
+
+// Compare operators on dynamic Dyon variables, used by code without type annotations.
+// The operator is dispatched at runtime, like Dyon does.
+
+fn invalid_types(op: &str) -> ! {
+    panic!("Invalid types for compare operator `{}`", op)
+}
+
+/// Compares two variables for equality.
+///
+/// Arrays, objects and options are compared by their contents.
+fn variable_equal(a: &Variable, b: &Variable, op: &str) -> bool {
+    use dyon::Variable::*;
+
+    match (a, b) {
+        (&F64(a, _), &F64(b, _)) => a == b,
+        (&Bool(a, _), &Bool(b, _)) => a == b,
+        (&Vec4(a), &Vec4(b)) => a == b,
+        (&Text(ref a), &Text(ref b)) => a == b,
+        (&Array(ref a), &Array(ref b)) => {
+            a.len() == b.len() &&
+            a.iter().zip(b.iter()).all(|(a, b)| variable_equal(a, b, op))
+        }
+        (&Object(ref a), &Object(ref b)) => {
+            a.len() == b.len() &&
+            a.iter().all(|(key, a)| match b.get(key) {
+                Some(b) => variable_equal(a, b, op),
+                None => false,
+            })
+        }
+        (&Option(None), &Option(None)) => true,
+        (&Option(Some(ref a)), &Option(Some(ref b))) => variable_equal(a, b, op),
+        (&Option(_), &Option(_)) => false,
+        _ => invalid_types(op),
+    }
+}
+
+impl Equal for Variable {
+    type Output = bool;
+
+    fn equal(&self, other: &Variable) -> bool {
+        variable_equal(self, other, "==")
+    }
+}
+
+impl NotEqual for Variable {
+    type Output = bool;
+
+    fn not_equal(&self, other: &Variable) -> bool {
+        !variable_equal(self, other, "!=")
+    }
+}
+
+/// Implements ordering of dynamic variables, which is supported for numbers and text.
+macro_rules! variable_order {
+    ($op:ident, $method:ident, $cmp:tt) => {
+        impl $op for Variable {
+            type Output = bool;
+
+            fn $method(&self, other: &Variable) -> bool {
+                use dyon::Variable::*;
+
+                match (self, other) {
+                    (&F64(a, _), &F64(b, _)) => a $cmp b,
+                    (&Text(ref a), &Text(ref b)) => a $cmp b,
+                    _ => invalid_types(stringify!($cmp)),
+                }
+            }
+        }
+    }
+}
+
+variable_order!(Less, less, <);
+variable_order!(LessOrEqual, less_or_equal, <=);
+variable_order!(Greater, greater, >);
+variable_order!(GreaterOrEqual, greater_or_equal, >=);
+
+/// Implements operators between dynamic variables and other types,
+/// by converting the other value into a variable.
+macro_rules! variable_other {
+    ($op:ident, $method:ident, $ty:ty) => {
+        impl $op<$ty> for Variable {
+            type Output = bool;
+
+            fn $method(&self, other: &$ty) -> bool {
+                self.$method(&other.to_variable())
+            }
+        }
+
+        impl $op<Variable> for $ty {
+            type Output = bool;
+
+            fn $method(&self, other: &Variable) -> bool {
+                self.to_variable().$method(other)
+            }
+        }
+
+        impl<'a> $op<$ty> for &'a Variable {
+            type Output = bool;
+
+            fn $method(&self, other: &$ty) -> bool {
+                (*self).$method(other)
+            }
+        }
+
+        impl<'a> $op<Variable> for &'a $ty {
+            type Output = bool;
+
+            fn $method(&self, other: &Variable) -> bool {
+                (*self).$method(other)
+            }
+        }
+    }
+}
+
+variable_other!(Less, less, f64);
+variable_other!(LessOrEqual, less_or_equal, f64);
+variable_other!(Greater, greater, f64);
+variable_other!(GreaterOrEqual, greater_or_equal, f64);
+variable_other!(Equal, equal, f64);
+variable_other!(NotEqual, not_equal, f64);
+variable_other!(Less, less, Arc<String>);
+variable_other!(LessOrEqual, less_or_equal, Arc<String>);
+variable_other!(Greater, greater, Arc<String>);
+variable_other!(GreaterOrEqual, greater_or_equal, Arc<String>);
+variable_other!(Equal, equal, Arc<String>);
+variable_other!(NotEqual, not_equal, Arc<String>);
+variable_other!(Equal, equal, bool);
+variable_other!(NotEqual, not_equal, bool);
+variable_other!(Equal, equal, [f32; 4]);
+variable_other!(NotEqual, not_equal, [f32; 4]);
@@ -0,0 +1,346 @@
+//! State shared while generating code.
+
+use std::cell::{Cell, RefCell};
+use std::sync::Arc;
+
+use dyon::Module;
+
+use current::Currents;
+use infer::Types;
+use intrinsics;
+use {CodeSettings, Diagnostic, TranspileError};
+
+/// Rust keywords that can not be used as variable names.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "become", "box", "break", "const", "continue", "crate",
+    "do", "else", "enum", "extern", "false", "final", "fn", "for", "if",
+    "impl", "in", "let", "loop", "macro", "match", "mod", "move", "mut",
+    "override", "priv", "pub", "ref", "return", "self", "Self", "static",
+    "struct", "super", "trait", "true", "type", "typeof", "unsafe", "unsized",
+    "use", "virtual", "where", "while", "yield",
+];
+
+/// Returns `true` if the name is a Rust keyword.
+pub fn is_keyword(name: &str) -> bool {
+    KEYWORDS.iter().any(|&kw| kw == name)
+}
+
+/// Runtime functions imported by generated code, besides `intrinsics::SUPPORTED`.
+const RUNTIME_FUNCTIONS: &[&str] = &[
+    "assign", "cond", "from_variable", "iter", "owned", "try_res", "variable",
+];
+
+/// Returns the name of a function without the mutability of arguments.
+fn base_name(name: &str) -> &str {
+    match name.find('(') {
+        Some(ind) => &name[..ind],
+        None => name,
+    }
+}
+
+/// Information about a variable on the stack.
+#[derive(Clone, Default)]
+struct Var {
+    /// The Rust name, when using variable names.
+    name: Option<String>,
+    /// The name of the struct, when the variable is an object with static keys.
+    object_struct: Option<String>,
+    /// The Dyon name, when the variable is a current object.
+    current: Option<Arc<String>>,
+    /// Whether the current object is a function argument.
+    current_arg: bool,
+}
+
+/// Returns the Rust name of each function.
+///
+/// Dyon functions with mutable arguments are named like `foo(mut,_)`.
+/// When another function has the same name with different mutability,
+/// the mutability of the arguments is added to the name, e.g. `foo_mut_`.
+/// If another function already has that name, a number is added, e.g. `foo_mut_1`.
+fn rust_names(module: &Module) -> Vec<String> {
+    let mut mangled = vec![];
+    let mut names: Vec<String> = module.functions.iter().map(|f| {
+        let base = base_name(&f.name);
+        let overloaded = module.functions.iter()
+            .any(|g| g.name != f.name && base_name(&g.name) == base);
+        if !overloaded || base.len() == f.name.len() {
+            mangled.push(false);
+            return String::from(base);
+        }
+
+        let mut name = String::from(base);
+        for arg in f.name[base.len() + 1..f.name.len() - 1].split(',') {
+            name.push('_');
+            if arg == "mut" {
+                name.push_str("mut");
+            }
+        }
+        mangled.push(true);
+        name
+    }).collect();
+
+    for i in 0..names.len() {
+        if !mangled[i] {continue}
+
+        let mut base = names[i].clone();
+        let mut name = base.clone();
+        if !base.ends_with('_') {base.push('_')}
+        let mut suffix = 1;
+        while names.iter().enumerate().any(|(j, n)| j != i && n == &name) {
+            name = format!("{}{}", base, suffix);
+            suffix += 1;
+        }
+        names[i] = name;
+    }
+    names
+}
+
+/// Returns the Rust module of each function, or `None` for the crate root.
+///
+/// Functions loaded from the same file as `main` (or the last function) are put in the root.
+/// Functions from other files, e.g. imported by a loader script,
+/// are put in a module named after the file.
+fn rust_modules(module: &Module) -> Vec<Option<String>> {
+    use std::path::Path;
+
+    let root = match module.functions.iter().find(|f| &**f.name == "main") {
+        Some(f) => f.file.clone(),
+        None => match module.functions.last() {
+            Some(f) => f.file.clone(),
+            None => return vec![],
+        }
+    };
+
+    let mut files: Vec<(Arc<String>, String)> = vec![];
+    module.functions.iter().map(|f| {
+        if f.file == root {return None}
+        if let Some(&(_, ref name)) = files.iter().find(|&&(ref file, _)| file == &f.file) {
+            return Some(name.clone());
+        }
+
+        let stem = Path::new(&**f.file).file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or(String::from("module"));
+        let mut base: String = stem.chars()
+            .map(|c| if c.is_alphanumeric() || c == '_' {c} else {'_'})
+            .collect();
+        if is_keyword(&base) || base.chars().next().map(|c| c.is_numeric()).unwrap_or(true) {
+            base.push('_');
+        }
+        let mut name = base.clone();
+        let mut suffix = 1;
+        while files.iter().any(|&(_, ref n)| n == &name) {
+            name = format!("{}{}", base, suffix);
+            suffix += 1;
+        }
+        files.push((f.file.clone(), name.clone()));
+        Some(name)
+    }).collect()
+}
+
+/// A Rust struct generated for objects with static keys.
+pub struct ObjectStruct {
+    pub name: String,
+    /// Field names and Rust types.
+    pub fields: Vec<(Arc<String>, &'static str)>,
+}
+
+/// Keeps track of the module, settings and variable names.
+pub struct Context<'a> {
+    pub module: &'a Module,
+    pub settings: &'a CodeSettings,
+    pub types: Types,
+    pub currents: Currents,
+    /// The Rust name of each function, see `rust_names`.
+    pub rust_names: Vec<String>,
+    /// The Rust module of each function, see `rust_modules`.
+    pub rust_modules: Vec<Option<String>>,
+    /// The index of the function that is being generated.
+    pub function: Cell<usize>,
+    /// Variables indexed by stack position.
+    vars: RefCell<Vec<Var>>,
+    /// Structs generated for objects with static keys.
+    pub object_structs: RefCell<Vec<ObjectStruct>>,
+    /// Collects errors instead of stopping at the first one, when checking.
+    diagnostics: Option<RefCell<Vec<Diagnostic>>>,
+}
+
+impl<'a> Context<'a> {
+    pub fn new(module: &'a Module, settings: &'a CodeSettings) -> Context<'a> {
+        let types = Types::new(module);
+        Context {
+            module: module,
+            settings: settings,
+            currents: Currents::new(module, &types),
+            types: types,
+            rust_names: rust_names(module),
+            rust_modules: rust_modules(module),
+            function: Cell::new(0),
+            vars: RefCell::new(vec![]),
+            object_structs: RefCell::new(vec![]),
+            diagnostics: None,
+        }
+    }
+
+    /// Creates a context that collects diagnostics.
+    pub fn checker(module: &'a Module, settings: &'a CodeSettings) -> Context<'a> {
+        Context {
+            diagnostics: Some(RefCell::new(vec![])),
+            ..Context::new(module, settings)
+        }
+    }
+
+    /// Returns the absolute index of a loaded function called from the current function.
+    ///
+    /// Dyon stores the index relative to the calling function,
+    /// such that modules can be composed by loader scripts.
+    pub fn loaded_function(&self, f_index: isize) -> usize {
+        (self.function.get() as isize + f_index) as usize
+    }
+
+    /// Returns the path prefix for calling a function from the current function.
+    pub fn function_path(&self, index: usize) -> String {
+        match (&self.rust_modules[self.function.get()], &self.rust_modules[index]) {
+            (from, to) if from == to => String::new(),
+            (&None, &Some(ref to)) => format!("{}::", to),
+            (&Some(_), &Some(ref to)) => format!("super::{}::", to),
+            (&Some(_), &None) => String::from("super::"),
+            (&None, &None) => String::new(),
+        }
+    }
+
+    /// Reports an error in the current function.
+    ///
+    /// When checking, the error is recorded and code generation continues.
+    pub fn report(&self, err: TranspileError) -> Result<(), TranspileError> {
+        let f = &self.module.functions[self.function.get()];
+        let err = err.locate(&f.source);
+        if let Some(ref diagnostics) = self.diagnostics {
+            diagnostics.borrow_mut().push(Diagnostic {
+                function: f.name.clone(),
+                error: err,
+            });
+            Ok(())
+        } else {
+            Err(err)
+        }
+    }
+
+    /// Returns `true` if the context collects diagnostics.
+    pub fn is_checking(&self) -> bool {
+        self.diagnostics.is_some()
+    }
+
+    /// Returns the collected diagnostics.
+    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+        match self.diagnostics {
+            Some(ref diagnostics) => diagnostics.replace(vec![]),
+            None => vec![],
+        }
+    }
+
+    /// Declares a variable at a stack position and returns its Rust name.
+    ///
+    /// Variables above the stack position are out of scope.
+    pub fn declare(&self, pos: usize, name: &Arc<String>) -> String {
+        let mut vars = self.vars.borrow_mut();
+        vars.truncate(pos);
+        while vars.len() < pos {
+            vars.push(Var::default());
+        }
+
+        if !self.settings.variable_names {
+            vars.push(Var::default());
+            return format!("_{}", pos);
+        }
+
+        // Avoid keywords and functions, since local variables shadow functions in Rust.
+        // This includes functions with mutable arguments, intrinsics and runtime functions.
+        let is_function = self.rust_names.iter().any(|n| n == &**name) ||
+            self.module.functions.iter().any(|f| base_name(&f.name) == &**name) ||
+            intrinsics::is_supported(name) ||
+            RUNTIME_FUNCTIONS.iter().any(|&n| n == &**name);
+        let mut base: String = (**name).clone();
+        if is_function || is_keyword(&base) {
+            base.push('_');
+        }
+
+        let mut res = base.clone();
+        let mut suffix = 1;
+        while vars.iter().any(|v| v.name.as_ref() == Some(&res)) {
+            res = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+        vars.push(Var {name: Some(res.clone()), ..Var::default()});
+        res
+    }
+
+    /// Declares a current object at a stack position and returns its Rust name.
+    pub fn declare_current(&self, pos: usize, name: &Arc<String>, arg: bool) -> String {
+        let res = self.declare(pos, name);
+        if let Some(var) = self.vars.borrow_mut().get_mut(pos) {
+            var.current = Some(name.clone());
+            var.current_arg = arg;
+        }
+        res
+    }
+
+    /// Generates code that passes a current object to a function.
+    ///
+    /// Current objects that are not declared in the function are passed through.
+    /// Declared current objects are borrowed mutably when the function mutates them.
+    pub fn pass_current(&self, name: &Arc<String>, mutable: bool) -> String {
+        let vars = self.vars.borrow();
+        for (pos, var) in vars.iter().enumerate().rev() {
+            if var.current.as_ref() == Some(name) {
+                let rust_name = match var.name {
+                    Some(ref rust_name) => rust_name.clone(),
+                    None => format!("_{}", pos),
+                };
+                return if var.current_arg {
+                    rust_name
+                } else if mutable {
+                    format!("&mut {}", rust_name)
+                } else {
+                    format!("&{}", rust_name)
+                };
+            }
+        }
+        format!("current_{}", name)
+    }
+
+    /// Returns the Rust name of a variable at a stack position.
+    pub fn name(&self, pos: usize) -> String {
+        if let Some(&Var {name: Some(ref name), ..}) = self.vars.borrow().get(pos) {
+            name.clone()
+        } else {
+            format!("_{}", pos)
+        }
+    }
+
+    /// Returns the name of a struct with the fields, adding it if it does not exist.
+    pub fn object_struct(&self, fields: Vec<(Arc<String>, &'static str)>) -> String {
+        let mut object_structs = self.object_structs.borrow_mut();
+        if let Some(s) = object_structs.iter().find(|s| s.fields == fields) {
+            return s.name.clone();
+        }
+        let name = format!("Object{}", object_structs.len());
+        object_structs.push(ObjectStruct {name: name.clone(), fields: fields});
+        name
+    }
+
+    /// Marks a declared variable as a struct generated from an object.
+    pub fn set_object_struct(&self, pos: usize, name: String) {
+        if let Some(var) = self.vars.borrow_mut().get_mut(pos) {
+            var.object_struct = Some(name);
+        }
+    }
+
+    /// Returns `true` if the variable is a struct generated from an object.
+    pub fn is_object_struct(&self, pos: usize) -> bool {
+        match self.vars.borrow().get(pos) {
+            Some(var) => var.object_struct.is_some(),
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Current objects.
+//!
+//! Dyon looks up current objects dynamically when calling a function.
+//! The generated code passes them as extra arguments instead,
+//! also through functions that do not use them, but call functions that do.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use dyon::{FnIndex, Module};
+use dyon::ast::{AssignOp, Expression};
+use dyon::ty::Type;
+
+use infer::Types;
+use visit::walk_block;
+
+/// Keeps track of current objects required by functions.
+pub struct Currents {
+    /// Current objects required by each function, in argument order.
+    ///
+    /// Starts with the current objects declared by the function.
+    pub needs: Vec<Vec<Arc<String>>>,
+    /// Current objects each function needs to mutate,
+    /// declared with `~ mut` by the function or a function it calls.
+    mutable: Vec<Vec<Arc<String>>>,
+    /// The Rust types of current objects, from the types of declarations.
+    types: HashMap<Arc<String>, &'static str>,
+}
+
+impl Currents {
+    pub fn new(module: &Module, inferred: &Types) -> Currents {
+        let n = module.functions.len();
+        let mut calls: Vec<Vec<usize>> = vec![vec![]; n];
+        let mut declared: Vec<Vec<Arc<String>>> = vec![vec![]; n];
+        let mut types: HashMap<Arc<String>, &'static str> = HashMap::new();
+        for (i, f) in module.functions.iter().enumerate() {
+            walk_block(&f.block, &mut |exp| {
+                match *exp {
+                    Expression::Call(ref call) => {
+                        if let FnIndex::Loaded(f_index) = call.f_index.get() {
+                            // Indices are relative to the calling function.
+                            calls[i].push((i as isize + f_index) as usize);
+                        }
+                    }
+                    Expression::Assign(ref assign) => {
+                        if let AssignOp::Assign = assign.op {} else {return}
+                        let item = if let Expression::Item(ref item) = assign.left {
+                            item
+                        } else {
+                            return
+                        };
+                        if !item.current {return}
+
+                        declared[i].push(item.name.clone());
+                        let ty = match inferred.get(&assign.right) {
+                            Some(&Type::F64) => "f64",
+                            Some(&Type::Bool) => "bool",
+                            Some(&Type::Vec4) => "[f32; 4]",
+                            Some(&Type::Text) => "Arc<String>",
+                            _ => "Variable",
+                        };
+                        let entry = types.entry(item.name.clone()).or_insert(ty);
+                        if *entry != ty {
+                            *entry = "Variable";
+                        }
+                    }
+                    _ => {}
+                }
+            });
+        }
+
+        let mut needs: Vec<Vec<Arc<String>>> = module.functions.iter()
+            .map(|f| f.currents.iter().map(|c| c.name.clone()).collect())
+            .collect();
+        let mut mutable: Vec<Vec<Arc<String>>> = module.functions.iter()
+            .map(|f| f.currents.iter().filter(|c| c.mutable).map(|c| c.name.clone()).collect())
+            .collect();
+        loop {
+            let mut changed = false;
+            for i in 0..n {
+                for &j in &calls[i] {
+                    for k in 0..needs[j].len() {
+                        let name = needs[j][k].clone();
+                        if !declared[i].contains(&name) && !needs[i].contains(&name) {
+                            needs[i].push(name);
+                            changed = true;
+                        }
+                    }
+                    for k in 0..mutable[j].len() {
+                        let name = mutable[j][k].clone();
+                        if !declared[i].contains(&name) && !mutable[i].contains(&name) {
+                            mutable[i].push(name);
+                            changed = true;
+                        }
+                    }
+                }
+            }
+            if !changed {break}
+        }
+
+        Currents {
+            needs: needs,
+            mutable: mutable,
+            types: types,
+        }
+    }
+
+    /// Returns `true` if a function needs to mutate a current object.
+    pub fn is_mutable(&self, f: usize, name: &Arc<String>) -> bool {
+        self.mutable[f].contains(name)
+    }
+
+    /// Returns the Rust type of a current object.
+    ///
+    /// Uses `Variable` when the type is not known.
+    pub fn ty(&self, name: &Arc<String>) -> &'static str {
+        self.types.get(name).cloned().unwrap_or("Variable")
+    }
+}
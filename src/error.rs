@@ -0,0 +1,188 @@
+//! Transpiler errors.
+
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::sync::Arc;
+
+use piston_meta::Range;
+
+/// Location of a node in the Dyon source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SourcePos {
+    /// The source range of the node.
+    pub range: Range,
+    /// Line and column (starting at 1), when the source is known.
+    pub line_col: Option<(usize, usize)>,
+}
+
+impl SourcePos {
+    /// Creates a new source position from a range.
+    pub fn new(range: Range) -> SourcePos {
+        SourcePos {range: range, line_col: None}
+    }
+
+    /// Computes line and column from the source text.
+    pub fn locate(&mut self, source: &str) {
+        if self.line_col.is_some() {return}
+
+        let mut line = 1;
+        let mut column = 1;
+        for (i, c) in source.char_indices() {
+            if i >= self.range.offset {break}
+            if c == '\n' {
+                line += 1;
+                column = 1;
+            } else {
+                column += 1;
+            }
+        }
+        self.line_col = Some((line, column));
+    }
+}
+
+impl fmt::Display for SourcePos {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some((line, column)) = self.line_col {
+            write!(f, "line {}, column {}", line, column)
+        } else {
+            write!(f, "offset {}", self.range.offset)
+        }
+    }
+}
+
+/// An error that occured while generating code.
+#[derive(Debug)]
+pub enum TranspileError {
+    /// The expression kind is not supported yet.
+    UnsupportedExpression {
+        /// The Dyon name of the expression kind, e.g. `go`.
+        kind: String,
+        /// Where the expression is.
+        pos: SourcePos,
+    },
+    /// The type has no Rust counterpart yet.
+    UnsupportedType {
+        /// The Dyon description of the type.
+        ty: String,
+        /// Where the type is declared.
+        pos: SourcePos,
+    },
+    /// An intrinsic that is not supported by the runtime yet.
+    UnsupportedIntrinsic {
+        /// The name of the intrinsic.
+        name: Arc<String>,
+        /// Where the call is.
+        pos: SourcePos,
+    },
+    /// The inferred type differs from the expected type.
+    TypeConflict {
+        /// The Dyon description of the expected type.
+        expected: String,
+        /// The Dyon description of the type that was found.
+        found: String,
+        /// Where the type was found.
+        pos: SourcePos,
+    },
+    /// An external function without a Rust function in `CodeSettings.externals`.
+    UnregisteredExternal {
+        /// The name of the function.
+        name: Arc<String>,
+        /// Where the call is.
+        pos: SourcePos,
+    },
+    /// A call that was not resolved by Dyon.
+    UnresolvedCall {
+        /// The name of the function.
+        name: Arc<String>,
+        /// Where the call is.
+        pos: SourcePos,
+    },
+    /// Failed to write the generated code.
+    Io(io::Error),
+}
+
+impl TranspileError {
+    /// Returns the position in the Dyon source, if any.
+    pub fn pos(&self) -> Option<&SourcePos> {
+        match *self {
+            TranspileError::UnsupportedExpression {ref pos, ..} |
+            TranspileError::UnsupportedType {ref pos, ..} |
+            TranspileError::UnsupportedIntrinsic {ref pos, ..} |
+            TranspileError::TypeConflict {ref pos, ..} |
+            TranspileError::UnregisteredExternal {ref pos, ..} |
+            TranspileError::UnresolvedCall {ref pos, ..} => Some(pos),
+            TranspileError::Io(_) => None,
+        }
+    }
+
+    /// Computes line and column of the error from the source text.
+    pub fn locate(mut self, source: &str) -> TranspileError {
+        match self {
+            TranspileError::UnsupportedExpression {ref mut pos, ..} |
+            TranspileError::UnsupportedType {ref mut pos, ..} |
+            TranspileError::UnsupportedIntrinsic {ref mut pos, ..} |
+            TranspileError::TypeConflict {ref mut pos, ..} |
+            TranspileError::UnregisteredExternal {ref mut pos, ..} |
+            TranspileError::UnresolvedCall {ref mut pos, ..} => pos.locate(source),
+            TranspileError::Io(_) => {}
+        }
+        self
+    }
+}
+
+impl fmt::Display for TranspileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            TranspileError::UnsupportedExpression {ref kind, ref pos} => {
+                write!(f, "{}: `{}` expressions are not supported yet", pos, kind)
+            }
+            TranspileError::UnsupportedType {ref ty, ref pos} => {
+                write!(f, "{}: The type `{}` is not supported yet", pos, ty)
+            }
+            TranspileError::UnsupportedIntrinsic {ref name, ref pos} => {
+                write!(f, "{}: The intrinsic `{}` is not supported yet", pos, name)
+            }
+            TranspileError::TypeConflict {ref expected, ref found, ref pos} => {
+                write!(f, "{}: Expected type `{}`, found `{}`", pos, expected, found)
+            }
+            TranspileError::UnregisteredExternal {ref name, ref pos} => {
+                write!(f, "{}: The external function `{}` has no registered Rust function", pos, name)
+            }
+            TranspileError::UnresolvedCall {ref name, ref pos} => {
+                write!(f, "{}: Could not resolve call to `{}`", pos, name)
+            }
+            TranspileError::Io(ref err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl Error for TranspileError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            TranspileError::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for TranspileError {
+    fn from(err: io::Error) -> TranspileError {
+        TranspileError::Io(err)
+    }
+}
+
+/// Reports an unsupported feature found when checking a module.
+#[derive(Debug)]
+pub struct Diagnostic {
+    /// The name of the function where the error is.
+    pub function: Arc<String>,
+    /// The error.
+    pub error: TranspileError,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "In function `{}`, {}", self.function, self.error)
+    }
+}
@@ -0,0 +1,51 @@
+//! External functions registered in the Dyon module from Rust.
+
+use std::collections::HashMap;
+
+/// How arguments are passed to an external function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArgConvention {
+    /// By reference, `&T`, like functions generated from Dyon.
+    Ref,
+    /// By value, `T`, using `owned`.
+    Value,
+    /// As dynamic values, `&Variable`.
+    Variable,
+}
+
+/// A Rust function called instead of an external function.
+#[derive(Clone, Debug)]
+pub struct ExternalFn {
+    /// The path of the Rust function, e.g. `engine::draw`.
+    pub path: String,
+    /// How arguments are passed.
+    ///
+    /// Mutable arguments are always passed as `&mut T`.
+    pub args: ArgConvention,
+}
+
+/// Maps names of external functions to Rust functions.
+#[derive(Clone, Debug, Default)]
+pub struct Externals {
+    functions: HashMap<String, ExternalFn>,
+}
+
+impl Externals {
+    /// Creates an empty registry.
+    pub fn new() -> Externals {
+        Externals {functions: HashMap::new()}
+    }
+
+    /// Registers a Rust function for an external function.
+    pub fn add(&mut self, name: &str, path: &str, args: ArgConvention) {
+        self.functions.insert(name.into(), ExternalFn {
+            path: path.into(),
+            args: args,
+        });
+    }
+
+    /// Returns the Rust function registered for an external function.
+    pub fn get(&self, name: &str) -> Option<&ExternalFn> {
+        self.functions.get(name)
+    }
+}
@@ -1,5 +1,7 @@
 //! Indexing helper functions.
 
+use std::sync::Arc;
+
 use dyon::Variable;
 
 pub trait Index {
@@ -20,6 +22,12 @@ impl<'a> Index for &'a str {
     fn index(self) -> &'a str {self}
 }
 
+impl<'a> Index for &'a Arc<String> {
+    type Output = &'a Arc<String>;
+
+    fn index(self) -> &'a Arc<String> {self}
+}
+
 /// Converts a type for index lookup.
 pub fn ind<T: Index>(a: T) -> T::Output {
     a.index()
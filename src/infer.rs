@@ -0,0 +1,445 @@
+//! Local type inference.
+//!
+//! Infers the types of expressions before generating code,
+//! using the types declared in function signatures.
+//! Types that can not be inferred are left out,
+//! and `any` means the value is a dynamic Dyon variable.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use dyon::{FnIndex, Module};
+use dyon::ast::*;
+use dyon::ty::Type;
+use piston_meta::Range;
+
+use intrinsics;
+
+/// Two types that do not match.
+#[derive(Clone, Debug)]
+pub struct Conflict {
+    /// The expected type.
+    pub expected: Type,
+    /// The type that was found.
+    pub found: Type,
+    /// Where the type was found.
+    pub range: Range,
+}
+
+/// Inferred types of expressions in a module.
+pub struct Types {
+    /// Types by the address of expressions in the module.
+    types: HashMap<usize, Type>,
+    /// Type conflicts in each function.
+    conflicts: Vec<Vec<Conflict>>,
+}
+
+impl Types {
+    pub fn new(module: &Module) -> Types {
+        let mut types = Types {
+            types: HashMap::new(),
+            conflicts: vec![],
+        };
+        for (i, f) in module.functions.iter().enumerate() {
+            let mut infer = Infer {
+                module: module,
+                function: i,
+                ret: f.ret.clone(),
+                scope: vec![],
+                types: &mut types.types,
+                conflicts: vec![],
+            };
+            for arg in &f.args {
+                infer.scope.push((arg.name.clone(), Some(arg.ty.clone())));
+            }
+            for current in &f.currents {
+                infer.scope.push((current.name.clone(), None));
+            }
+            infer.block(&f.block);
+            let conflicts = infer.conflicts;
+            types.conflicts.push(conflicts);
+        }
+        types
+    }
+
+    /// Returns the inferred type of an expression.
+    pub fn get(&self, exp: &Expression) -> Option<&Type> {
+        self.types.get(&key(exp))
+    }
+
+    /// Returns `true` if the expression is a dynamic Dyon variable.
+    pub fn is_variable(&self, exp: &Expression) -> bool {
+        match self.get(exp) {
+            Some(&Type::Any) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns the type conflicts in a function.
+    pub fn conflicts(&self, function: usize) -> &[Conflict] {
+        &self.conflicts[function]
+    }
+}
+
+fn key(exp: &Expression) -> usize {
+    exp as *const Expression as usize
+}
+
+/// Returns `true` if a value of a type can be used where another type is expected.
+///
+/// Only reports types that clearly differ, since Dyon's type checker has already run.
+fn compatible(expected: &Type, found: &Type) -> bool {
+    match (expected, found) {
+        (&Type::Any, _) | (_, &Type::Any) => true,
+        (&Type::Secret(ref a), b) | (b, &Type::Secret(ref a)) => compatible(a, b),
+        (&Type::Array(ref a), &Type::Array(ref b)) |
+        (&Type::Option(ref a), &Type::Option(ref b)) |
+        (&Type::Result(ref a), &Type::Result(ref b)) |
+        (&Type::Thread(ref a), &Type::Thread(ref b)) => compatible(a, b),
+        (&Type::F64, &Type::F64) |
+        (&Type::Bool, &Type::Bool) |
+        (&Type::Text, &Type::Text) |
+        (&Type::Vec4, &Type::Vec4) |
+        (&Type::Link, &Type::Link) |
+        (&Type::Object, &Type::Object) => true,
+        (&Type::F64, _) | (&Type::Bool, _) | (&Type::Text, _) | (&Type::Vec4, _) |
+        (&Type::Link, _) | (&Type::Object, _) | (&Type::Array(_), _) => false,
+        _ => true,
+    }
+}
+
+/// Returns the type of an array from the types of its items.
+///
+/// Items of different types are stored as `any`, even if the type of some items is unknown.
+fn array_type(items: &[Option<Type>]) -> Option<Type> {
+    let mut known = items.iter().filter_map(|ty| ty.as_ref());
+    let first = match known.next() {
+        Some(ty) => ty,
+        None => return None,
+    };
+    if known.any(|ty| ty != first) {
+        Some(Type::Array(Box::new(Type::Any)))
+    } else if items.iter().any(|ty| ty.is_none()) {
+        None
+    } else {
+        Some(Type::Array(Box::new(first.clone())))
+    }
+}
+
+struct Infer<'a> {
+    module: &'a Module,
+    /// The index of the function.
+    function: usize,
+    /// The return type of the function or closure.
+    ret: Type,
+    /// Variables in scope.
+    scope: Vec<(Arc<String>, Option<Type>)>,
+    types: &'a mut HashMap<usize, Type>,
+    conflicts: Vec<Conflict>,
+}
+
+impl<'a> Infer<'a> {
+    fn check(&mut self, expected: &Type, found: &Option<Type>, range: Range) {
+        if let Some(ref found) = *found {
+            if !compatible(expected, found) {
+                self.conflicts.push(Conflict {
+                    expected: expected.clone(),
+                    found: found.clone(),
+                    range: range,
+                });
+            }
+        }
+    }
+
+    fn lookup(&self, name: &Arc<String>) -> Option<Type> {
+        self.scope.iter().rev().find(|&&(ref n, _)| n == name).and_then(|&(_, ref ty)| ty.clone())
+    }
+
+    fn block(&mut self, block: &Block) -> Option<Type> {
+        let len = self.scope.len();
+        let mut ty = Some(Type::Void);
+        for exp in &block.expressions {
+            ty = self.expression(exp);
+        }
+        self.scope.truncate(len);
+        ty
+    }
+
+    fn expression(&mut self, exp: &Expression) -> Option<Type> {
+        use dyon::ast::Expression as E;
+
+        let ty = match *exp {
+            E::Number(_) => Some(Type::F64),
+            E::Bool(_) => Some(Type::Bool),
+            E::Text(_) => Some(Type::Text),
+            E::Vec4(ref vec4) => {
+                for arg in &vec4.args {
+                    self.expression(arg);
+                }
+                Some(Type::Vec4)
+            }
+            E::Link(ref link) => {
+                for item in &link.items {
+                    self.expression(item);
+                }
+                Some(Type::Link)
+            }
+            E::Object(ref obj) => {
+                for &(_, ref e) in &obj.key_values {
+                    self.expression(e);
+                }
+                Some(Type::Object)
+            }
+            E::Array(ref array) => {
+                let items: Vec<Option<Type>> = array.items.iter()
+                    .map(|item| self.expression(item))
+                    .collect();
+                array_type(&items)
+            }
+            E::Item(ref item) => self.item(item),
+            E::Call(ref call) => self.call(call),
+            E::Go(ref go) => self.call(&go.call).map(|ty| Type::Thread(Box::new(ty))),
+            E::BinOp(ref binop) => {
+                let left = self.expression(&binop.left);
+                let right = self.expression(&binop.right);
+                self.binop(left, right, binop.right.source_range())
+            }
+            E::Compare(ref compare) => {
+                let left = self.expression(&compare.left);
+                self.expression(&compare.right);
+                // The secret of the left argument is kept.
+                match left {
+                    Some(Type::Secret(_)) => Some(Type::Secret(Box::new(Type::Bool))),
+                    _ => Some(Type::Bool),
+                }
+            }
+            E::UnOp(ref unop) => {
+                let ty = self.expression(&unop.expr);
+                match (&unop.op, ty) {
+                    (&UnOp::Not, Some(Type::Secret(ty))) => Some(Type::Secret(ty)),
+                    (&UnOp::Not, _) => Some(Type::Bool),
+                    (_, ty) => ty,
+                }
+            }
+            E::Assign(ref assign) => {
+                self.assign(assign);
+                Some(Type::Void)
+            }
+            E::Return(ref e) => {
+                let ty = self.expression(e);
+                let ret = self.ret.clone();
+                self.check(&ret, &ty, e.source_range());
+                Some(Type::Void)
+            }
+            E::Try(ref e) => {
+                match self.expression(e) {
+                    Some(Type::Result(ty)) | Some(Type::Option(ty)) => Some(*ty),
+                    _ => None,
+                }
+            }
+            E::Block(ref block) => self.block(block),
+            E::If(ref if_expr) => {
+                self.expression(&if_expr.cond);
+                let mut ty = self.block(&if_expr.true_block);
+                for (cond, block) in if_expr.else_if_conds.iter().zip(if_expr.else_if_blocks.iter()) {
+                    self.expression(cond);
+                    let block_ty = self.block(block);
+                    ty = if ty == block_ty {ty} else {None};
+                }
+                match if_expr.else_block {
+                    Some(ref block) => {
+                        let block_ty = self.block(block);
+                        if ty == block_ty {ty} else {None}
+                    }
+                    None => Some(Type::Void),
+                }
+            }
+            E::For(ref for_expr) => {
+                let len = self.scope.len();
+                self.expression(&for_expr.init);
+                self.expression(&for_expr.cond);
+                self.expression(&for_expr.step);
+                self.block(&for_expr.block);
+                self.scope.truncate(len);
+                Some(Type::Void)
+            }
+            E::Loop(ref loop_expr) => {
+                self.block(&loop_expr.block);
+                Some(Type::Void)
+            }
+            E::ForN(ref for_n) => {
+                self.for_n(for_n);
+                Some(Type::Void)
+            }
+            E::Sum(ref for_n) | E::Prod(ref for_n) => {
+                self.for_n(for_n);
+                Some(Type::F64)
+            }
+            E::Min(ref for_n) | E::Max(ref for_n) => {
+                self.for_n(for_n);
+                Some(Type::Secret(Box::new(Type::F64)))
+            }
+            E::Any(ref for_n) | E::All(ref for_n) => {
+                self.for_n(for_n);
+                Some(Type::Secret(Box::new(Type::Bool)))
+            }
+            E::Sift(ref for_n) => self.for_n(for_n).map(|ty| Type::Array(Box::new(ty))),
+            E::ForIn(ref for_in) => {
+                self.for_in(for_in);
+                Some(Type::Void)
+            }
+            E::SumIn(ref for_in) | E::ProdIn(ref for_in) => {
+                self.for_in(for_in);
+                Some(Type::F64)
+            }
+            E::MinIn(ref for_in) | E::MaxIn(ref for_in) => {
+                self.for_in(for_in);
+                Some(Type::Secret(Box::new(Type::F64)))
+            }
+            E::AnyIn(ref for_in) | E::AllIn(ref for_in) => {
+                self.for_in(for_in);
+                Some(Type::Secret(Box::new(Type::Bool)))
+            }
+            E::SiftIn(ref for_in) => self.for_in(for_in).map(|ty| Type::Array(Box::new(ty))),
+            E::Closure(ref closure) => {
+                let len = self.scope.len();
+                for arg in &closure.args {
+                    self.scope.push((arg.name.clone(), Some(arg.ty.clone())));
+                }
+                let ret = ::std::mem::replace(&mut self.ret, closure.ret.clone());
+                self.expression(&closure.expr);
+                self.ret = ret;
+                self.scope.truncate(len);
+                None
+            }
+            E::CallClosure(ref call_closure) => {
+                self.item(&call_closure.item);
+                for arg in &call_closure.args {
+                    self.expression(arg);
+                }
+                None
+            }
+            E::Swizzle(ref swizzle) => {
+                self.expression(&swizzle.expr);
+                None
+            }
+            _ => None,
+        };
+        if let Some(ref ty) = ty {
+            self.types.insert(key(exp), ty.clone());
+        }
+        ty
+    }
+
+    fn item(&mut self, item: &Item) -> Option<Type> {
+        let mut ty = self.lookup(&item.name);
+        for id in &item.ids {
+            if let Id::Expression(ref exp) = *id {
+                self.expression(exp);
+            }
+            ty = match (ty, id) {
+                (Some(Type::Array(ty)), &Id::F64(_, _)) |
+                (Some(Type::Array(ty)), &Id::Expression(_)) => Some(*ty),
+                // Objects might be generated as structs with typed fields.
+                _ => None,
+            };
+        }
+        ty
+    }
+
+    fn call(&mut self, call: &Call) -> Option<Type> {
+        let tys: Vec<Option<Type>> = call.args.iter().map(|arg| self.expression(arg)).collect();
+        match call.f_index.get() {
+            FnIndex::Loaded(f_index) => {
+                let module = self.module;
+                let f = &module.functions[(self.function as isize + f_index) as usize];
+                // Swizzles expand into several arguments.
+                let swizzle = call.args.iter().any(|arg| {
+                    if let Expression::Swizzle(_) = *arg {true} else {false}
+                });
+                if !swizzle {
+                    for ((arg, ty), exp) in f.args.iter().zip(tys.iter()).zip(call.args.iter()) {
+                        self.check(&arg.ty, ty, exp.source_range());
+                    }
+                }
+                Some(f.ret.clone())
+            }
+            FnIndex::Intrinsic(_) => {
+                let name = match call.name.find('(') {
+                    Some(ind) => &call.name[..ind],
+                    None => &call.name[..],
+                };
+                let arg = tys.into_iter().next().and_then(|ty| ty);
+                intrinsics::return_type(name).and_then(|ret| ret.infer(arg))
+            }
+            _ => None,
+        }
+    }
+
+    fn binop(&mut self, left: Option<Type>, right: Option<Type>, range: Range) -> Option<Type> {
+        let (left, right) = match (left, right) {
+            (Some(left), Some(right)) => (left, right),
+            _ => return None,
+        };
+        match (&left, &right) {
+            (&Type::Any, _) | (_, &Type::Any) => Some(Type::Any),
+            (&Type::Secret(ref a), b) | (b, &Type::Secret(ref a)) => {
+                self.binop(Some((**a).clone()), Some(b.clone()), range)
+            }
+            (&Type::F64, &Type::F64) => Some(Type::F64),
+            (&Type::Vec4, &Type::Vec4) | (&Type::Vec4, &Type::F64) | (&Type::F64, &Type::Vec4) => {
+                Some(Type::Vec4)
+            }
+            (&Type::Bool, &Type::Bool) => Some(Type::Bool),
+            (&Type::Text, &Type::Text) => Some(Type::Text),
+            _ => {
+                self.check(&left, &Some(right.clone()), range);
+                None
+            }
+        }
+    }
+
+    fn assign(&mut self, assign: &Assign) {
+        let right = self.expression(&assign.right);
+        if let Expression::Item(ref item) = assign.left {
+            match assign.op {
+                AssignOp::Assign if item.ids.is_empty() => {
+                    self.scope.push((item.name.clone(), right));
+                }
+                AssignOp::Set => {
+                    if let Some(left) = self.item(item) {
+                        self.check(&left, &right, assign.right.source_range());
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Returns the type of the loop body.
+    fn for_n(&mut self, for_n: &ForN) -> Option<Type> {
+        if let Some(ref start) = for_n.start {
+            self.expression(start);
+        }
+        self.expression(&for_n.end);
+        let len = self.scope.len();
+        self.scope.push((for_n.name.clone(), Some(Type::F64)));
+        let ty = self.block(&for_n.block);
+        self.scope.truncate(len);
+        ty
+    }
+
+    /// Returns the type of the loop body.
+    fn for_in(&mut self, for_in: &ForIn) -> Option<Type> {
+        let item_ty = match self.expression(&for_in.iter) {
+            Some(Type::Array(ty)) => Some(*ty),
+            Some(Type::Link) => Some(Type::Any),
+            _ => None,
+        };
+        let len = self.scope.len();
+        self.scope.push((for_in.name.clone(), item_ty));
+        let ty = self.block(&for_in.block);
+        self.scope.truncate(len);
+        ty
+    }
+}
@@ -0,0 +1,163 @@
+//! Array intrinsics that mutate the array.
+//!
+//! Arrays are either `Vec<T>` or dynamic variables holding an array.
+
+use std::sync::Arc;
+
+use dyon::Variable;
+
+use variable::ToVariable;
+use Owned;
+
+/// Implemented by types that can be mutated like Dyon arrays.
+pub trait ArrayMut {
+    type Item;
+
+    fn array_len(&self) -> usize;
+    fn array_push(&mut self, item: Self::Item);
+    fn array_pop(&mut self) -> Option<Self::Item>;
+    fn array_insert(&mut self, ind: usize, item: Self::Item);
+    fn array_remove(&mut self, ind: usize) -> Self::Item;
+    fn array_swap(&mut self, i: usize, j: usize);
+    fn array_reverse(&mut self);
+    fn array_clear(&mut self);
+}
+
+impl<T> ArrayMut for Vec<T> {
+    type Item = T;
+
+    fn array_len(&self) -> usize {self.len()}
+    fn array_push(&mut self, item: T) {self.push(item)}
+    fn array_pop(&mut self) -> Option<T> {self.pop()}
+    fn array_insert(&mut self, ind: usize, item: T) {self.insert(ind, item)}
+    fn array_remove(&mut self, ind: usize) -> T {self.remove(ind)}
+    fn array_swap(&mut self, i: usize, j: usize) {self.swap(i, j)}
+    fn array_reverse(&mut self) {self.reverse()}
+    fn array_clear(&mut self) {self.clear()}
+}
+
+impl<'a, A: ArrayMut + ?Sized> ArrayMut for &'a mut A {
+    type Item = A::Item;
+
+    fn array_len(&self) -> usize {(**self).array_len()}
+    fn array_push(&mut self, item: A::Item) {(**self).array_push(item)}
+    fn array_pop(&mut self) -> Option<A::Item> {(**self).array_pop()}
+    fn array_insert(&mut self, ind: usize, item: A::Item) {(**self).array_insert(ind, item)}
+    fn array_remove(&mut self, ind: usize) -> A::Item {(**self).array_remove(ind)}
+    fn array_swap(&mut self, i: usize, j: usize) {(**self).array_swap(i, j)}
+    fn array_reverse(&mut self) {(**self).array_reverse()}
+    fn array_clear(&mut self) {(**self).array_clear()}
+}
+
+/// Returns the array inside a dynamic variable.
+///
+/// Panics when the variable is not an array, like Dyon reports a runtime error.
+fn variable_array(var: &Variable) -> &Arc<Vec<Variable>> {
+    match *var {
+        Variable::Array(ref arr) => arr,
+        _ => panic!("Expected array"),
+    }
+}
+
+/// Returns the array inside a dynamic variable for mutation.
+fn variable_array_mut(var: &mut Variable) -> &mut Vec<Variable> {
+    match *var {
+        Variable::Array(ref mut arr) => Arc::make_mut(arr),
+        _ => panic!("Expected array"),
+    }
+}
+
+impl ArrayMut for Variable {
+    type Item = Variable;
+
+    fn array_len(&self) -> usize {variable_array(self).len()}
+    fn array_push(&mut self, item: Variable) {variable_array_mut(self).push(item)}
+    fn array_pop(&mut self) -> Option<Variable> {variable_array_mut(self).pop()}
+    fn array_insert(&mut self, ind: usize, item: Variable) {
+        variable_array_mut(self).insert(ind, item)
+    }
+    fn array_remove(&mut self, ind: usize) -> Variable {variable_array_mut(self).remove(ind)}
+    fn array_swap(&mut self, i: usize, j: usize) {variable_array_mut(self).swap(i, j)}
+    fn array_reverse(&mut self) {variable_array_mut(self).reverse()}
+    fn array_clear(&mut self) {variable_array_mut(self).clear()}
+}
+
+/// Converts an argument into an item of an array.
+///
+/// Typed arrays store owned values, while dynamic arrays store variables.
+pub trait ArrayItem<T: ?Sized>: ArrayMut {
+    fn array_item(item: &T) -> Self::Item;
+}
+
+impl<T: Owned + ?Sized> ArrayItem<T> for Vec<T::Output> {
+    fn array_item(item: &T) -> T::Output {item.owned()}
+}
+
+impl<'a, T: ?Sized, A: ArrayItem<T> + ?Sized> ArrayItem<T> for &'a mut A {
+    fn array_item(item: &T) -> A::Item {A::array_item(item)}
+}
+
+impl<T: ToVariable + ?Sized> ArrayItem<T> for Variable {
+    fn array_item(item: &T) -> Variable {item.to_variable()}
+}
+
+/// Converts an index argument, checking that it is within bounds.
+///
+/// Panics when the index is out of bounds, like Dyon reports a runtime error.
+fn index(ind: &f64, len: usize) -> usize {
+    if *ind < 0.0 || *ind as usize >= len {
+        panic!("Index out of bounds `{}`", ind);
+    }
+    *ind as usize
+}
+
+pub fn push<A: ArrayItem<T>, T: ?Sized>(arr: &mut A, item: &T) {
+    let item = A::array_item(item);
+    arr.array_push(item)
+}
+
+/// Pushes an item to an array.
+///
+/// Dyon pushes a reference, but generated code has no references into arrays.
+pub fn push_ref<A: ArrayItem<T>, T: ?Sized>(arr: &mut A, item: &T) {
+    push(arr, item)
+}
+
+/// Removes the last item of an array.
+///
+/// Panics when the array is empty, like Dyon reports a runtime error.
+pub fn pop<A: ArrayMut>(arr: &mut A) -> A::Item {
+    match arr.array_pop() {
+        Some(item) => item,
+        None => panic!("Expected non-empty array"),
+    }
+}
+
+/// Inserts an item before the index.
+///
+/// The index can be equal to the length of the array, which inserts at the end.
+pub fn insert<A: ArrayItem<T>, T: ?Sized>(arr: &mut A, ind: &f64, item: &T) {
+    let ind = index(ind, arr.array_len() + 1);
+    let item = A::array_item(item);
+    arr.array_insert(ind, item)
+}
+
+/// Removes an item at the index and returns it.
+pub fn remove<A: ArrayMut>(arr: &mut A, ind: &f64) -> A::Item {
+    let ind = index(ind, arr.array_len());
+    arr.array_remove(ind)
+}
+
+pub fn swap<A: ArrayMut>(arr: &mut A, i: &f64, j: &f64) {
+    let len = arr.array_len();
+    let (i, j) = (index(i, len), index(j, len));
+    arr.array_swap(i, j)
+}
+
+pub fn reverse<A: ArrayMut>(arr: &mut A) {
+    arr.array_reverse()
+}
+
+pub fn clear<A: ArrayMut>(arr: &mut A) {
+    arr.array_clear()
+}
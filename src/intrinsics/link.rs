@@ -0,0 +1,19 @@
+//! Dyon link structure.
+
+pub use dyon::link::Link;
+
+use dyon::Variable;
+
+/// Returns the first item of a link.
+pub fn head(link: &Link) -> Option<Variable> {
+    link.head().map(|v| *v)
+}
+
+/// Returns the link without the first item.
+pub fn tail(link: &Link) -> Link {
+    link.tail()
+}
+
+pub fn is_empty(link: &Link) -> bool {
+    link.is_empty()
+}
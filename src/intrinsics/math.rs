@@ -0,0 +1,156 @@
+//! Numeric intrinsics.
+//!
+//! Arguments are passed by reference, like other functions in generated code.
+
+use std::cell::Cell;
+use std::f64;
+
+pub fn sqrt(a: &f64) -> f64 {
+    a.sqrt()
+}
+
+pub fn sin(a: &f64) -> f64 {
+    a.sin()
+}
+
+pub fn asin(a: &f64) -> f64 {
+    a.asin()
+}
+
+pub fn cos(a: &f64) -> f64 {
+    a.cos()
+}
+
+pub fn acos(a: &f64) -> f64 {
+    a.acos()
+}
+
+pub fn tan(a: &f64) -> f64 {
+    a.tan()
+}
+
+pub fn atan(a: &f64) -> f64 {
+    a.atan()
+}
+
+pub fn atan2(y: &f64, x: &f64) -> f64 {
+    y.atan2(*x)
+}
+
+pub fn exp(a: &f64) -> f64 {
+    a.exp()
+}
+
+pub fn ln(a: &f64) -> f64 {
+    a.ln()
+}
+
+pub fn log2(a: &f64) -> f64 {
+    a.log2()
+}
+
+pub fn log10(a: &f64) -> f64 {
+    a.log10()
+}
+
+pub fn round(a: &f64) -> f64 {
+    a.round()
+}
+
+pub fn abs(a: &f64) -> f64 {
+    a.abs()
+}
+
+pub fn floor(a: &f64) -> f64 {
+    a.floor()
+}
+
+pub fn ceil(a: &f64) -> f64 {
+    a.ceil()
+}
+
+pub fn is_nan(a: &f64) -> bool {
+    a.is_nan()
+}
+
+pub fn tau() -> f64 {
+    6.283_185_307_179_586
+}
+
+/// Returns the smallest number in the array, or NaN if the array is empty.
+pub fn min(arr: &Vec<f64>) -> f64 {
+    let mut min = f64::NAN;
+    for &v in arr {
+        if v < min || min.is_nan() {
+            min = v;
+        }
+    }
+    min
+}
+
+/// Returns the largest number in the array, or NaN if the array is empty.
+pub fn max(arr: &Vec<f64>) -> f64 {
+    let mut max = f64::NAN;
+    for &v in arr {
+        if v > max || max.is_nan() {
+            max = v;
+        }
+    }
+    max
+}
+
+pub fn x(v: &[f32; 4]) -> f64 {
+    v[0] as f64
+}
+
+pub fn y(v: &[f32; 4]) -> f64 {
+    v[1] as f64
+}
+
+pub fn z(v: &[f32; 4]) -> f64 {
+    v[2] as f64
+}
+
+pub fn w(v: &[f32; 4]) -> f64 {
+    v[3] as f64
+}
+
+/// Returns a component of a 4D vector.
+///
+/// Panics when the index is out of bounds, like Dyon reports a runtime error.
+pub fn s(v: &[f32; 4], ind: &f64) -> f64 {
+    match v.get(*ind as usize) {
+        Some(&val) if *ind >= 0.0 => val as f64,
+        _ => panic!("Index out of bounds `{}`", ind),
+    }
+}
+
+thread_local! {
+    /// State of the random number generator.
+    static RANDOM: Cell<u64> = Cell::new(random_seed());
+}
+
+fn random_seed() -> u64 {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+
+    // `RandomState` is seeded randomly by the standard library.
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u64(0);
+    // The state of xorshift must not be zero.
+    hasher.finish() | 1
+}
+
+/// Returns a random number between 0 and 1.
+pub fn random() -> f64 {
+    RANDOM.with(|state| {
+        // Xorshift64*.
+        let mut x = state.get();
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        state.set(x);
+        let bits = x.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 11;
+        bits as f64 / (1u64 << 53) as f64
+    })
+}
@@ -1,11 +1,167 @@
 //! Maps functionality of the Dyon standard library.
 
+pub use self::array::{push, push_ref, pop, insert, remove, swap, reverse, clear};
+pub use self::array::{ArrayMut, ArrayItem};
+pub use self::link::{head, tail, is_empty, Link};
+pub use self::math::{sqrt, sin, asin, cos, acos, tan, atan, atan2, exp, ln, log2, log10};
+pub use self::math::{round, abs, floor, ceil, is_nan, tau, min, max, x, y, z, w, s, random};
 pub use self::print::print;
 pub use self::print::println;
+pub use self::print::{PrintLn, WriteLn};
+pub use self::result::{ok, err, some, none, is_ok, is_err, is_some, is_none};
+pub use self::result::{unwrap, unwrap_err, unwrap_or};
+pub use self::result::{try_res, TryRes};
+pub use self::text::{str, json_string, chars, trim, trim_left, trim_right, parse_number};
+pub use self::text::AsText;
+pub use self::thread::{join__thread, Thread};
+
+use dyon::ty::Type;
 
 use Secret;
 
+mod array;
+mod link;
+mod math;
 mod print;
+mod result;
+mod text;
+mod thread;
+
+/// Names of Dyon intrinsics supported by the runtime, with their return types.
+pub const SUPPORTED: &[(&str, ReturnType)] = &[
+    ("abs", ReturnType::F64),
+    ("acos", ReturnType::F64),
+    ("asin", ReturnType::F64),
+    ("atan", ReturnType::F64),
+    ("atan2", ReturnType::F64),
+    ("ceil", ReturnType::F64),
+    ("chars", ReturnType::TextArray),
+    ("clear", ReturnType::Unknown),
+    ("clone", ReturnType::Arg),
+    ("cos", ReturnType::F64),
+    ("err", ReturnType::Unknown),
+    ("exp", ReturnType::F64),
+    ("floor", ReturnType::F64),
+    ("head", ReturnType::Unknown),
+    ("insert", ReturnType::Unknown),
+    ("is_empty", ReturnType::Bool),
+    ("is_err", ReturnType::Bool),
+    ("is_nan", ReturnType::Bool),
+    ("is_none", ReturnType::Bool),
+    ("is_ok", ReturnType::Bool),
+    ("is_some", ReturnType::Bool),
+    ("join__thread", ReturnType::Unknown),
+    ("json_string", ReturnType::Text),
+    ("len", ReturnType::F64),
+    ("ln", ReturnType::F64),
+    ("log10", ReturnType::F64),
+    ("log2", ReturnType::F64),
+    ("max", ReturnType::F64),
+    ("min", ReturnType::F64),
+    ("none", ReturnType::Unknown),
+    ("ok", ReturnType::OkArg),
+    ("parse_number", ReturnType::OptionF64),
+    ("pop", ReturnType::Item),
+    ("print", ReturnType::Void),
+    ("println", ReturnType::Void),
+    ("push", ReturnType::Unknown),
+    ("push_ref", ReturnType::Unknown),
+    ("random", ReturnType::F64),
+    ("remove", ReturnType::Item),
+    ("reverse", ReturnType::Unknown),
+    ("round", ReturnType::F64),
+    ("s", ReturnType::F64),
+    ("sin", ReturnType::F64),
+    ("some", ReturnType::SomeArg),
+    ("sqrt", ReturnType::F64),
+    ("str", ReturnType::Text),
+    ("swap", ReturnType::Unknown),
+    ("tail", ReturnType::Link),
+    ("tan", ReturnType::F64),
+    ("tau", ReturnType::F64),
+    ("trim", ReturnType::Text),
+    ("trim_left", ReturnType::Text),
+    ("trim_right", ReturnType::Text),
+    ("unwrap", ReturnType::Unwrap),
+    ("unwrap_err", ReturnType::Unknown),
+    ("unwrap_or", ReturnType::Unknown),
+    ("w", ReturnType::F64),
+    ("where", ReturnType::Unknown),
+    ("why", ReturnType::Unknown),
+    ("x", ReturnType::F64),
+    ("y", ReturnType::F64),
+    ("z", ReturnType::F64),
+];
+
+/// The return type of an intrinsic, used by type inference.
+#[derive(Clone, Copy)]
+pub enum ReturnType {
+    /// The return type is not inferred.
+    Unknown,
+    /// No value.
+    Void,
+    /// `bool`.
+    Bool,
+    /// `f64`.
+    F64,
+    /// `str`.
+    Text,
+    /// `link`.
+    Link,
+    /// `[str]`.
+    TextArray,
+    /// `opt[f64]`.
+    OptionF64,
+    /// The type of the first argument.
+    Arg,
+    /// An option of the first argument.
+    SomeArg,
+    /// A result of the first argument.
+    OkArg,
+    /// The value inside the first argument, an option or result.
+    Unwrap,
+    /// The item type of the first argument, an array.
+    Item,
+}
+
+impl ReturnType {
+    /// Returns the type, given the type of the first argument if known.
+    pub fn infer(self, arg: Option<Type>) -> Option<Type> {
+        use self::ReturnType::*;
+
+        match self {
+            Unknown => None,
+            Void => Some(Type::Void),
+            Bool => Some(Type::Bool),
+            F64 => Some(Type::F64),
+            Text => Some(Type::Text),
+            Link => Some(Type::Link),
+            TextArray => Some(Type::Array(Box::new(Type::Text))),
+            OptionF64 => Some(Type::Option(Box::new(Type::F64))),
+            Arg => arg,
+            SomeArg => arg.map(|ty| Type::Option(Box::new(ty))),
+            OkArg => arg.map(|ty| Type::Result(Box::new(ty))),
+            Unwrap => match arg {
+                Some(Type::Option(ty)) | Some(Type::Result(ty)) => Some(*ty),
+                _ => None,
+            },
+            Item => match arg {
+                Some(Type::Array(ty)) => Some(*ty),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Returns `true` if the runtime supports the intrinsic.
+pub fn is_supported(name: &str) -> bool {
+    SUPPORTED.iter().any(|&(n, _)| n == name)
+}
+
+/// Returns the return type of a supported intrinsic.
+pub fn return_type(name: &str) -> Option<ReturnType> {
+    SUPPORTED.iter().find(|&&(n, _)| n == name).map(|&(_, ret)| ret)
+}
 
 pub fn len<T>(arr: &Vec<T>) -> f64 {
     arr.len() as f64
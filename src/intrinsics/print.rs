@@ -1,9 +1,10 @@
 use std::io;
 use std::sync::Arc;
 
-use dyon::{Object, Variable};
+use dyon::{Error, Object, Variable};
 
 use Secret;
+use super::Link;
 
 pub trait PrintLn {
     fn println(&self) {
@@ -14,15 +15,9 @@ pub trait PrintLn {
 }
 
 impl<T: WriteLn> PrintLn for T {
-    default fn print(&self) {
-        let mut stdout = io::stdout();
-        self.write(&mut stdout).unwrap();
-    }
-}
-
-impl<'a> PrintLn for &'a str {
     fn print(&self) {
-        print!("{}", self);
+        let mut stdout = io::stdout();
+        self.write_top(&mut stdout).unwrap();
     }
 }
 
@@ -40,12 +35,21 @@ pub trait WriteLn {
         self.write(w)?;
         write!(w, "")
     }
+    /// Writes the value when it is printed directly.
+    ///
+    /// Text is written without quotes, while text inside other values is quoted.
+    fn write_top<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.write(w)
+    }
 }
 
 impl<T: WriteLn> WriteLn for Arc<T> {
     fn write<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
         (**self).writeln(w)
     }
+    fn write_top<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        (**self).write_top(w)
+    }
 }
 
 impl<'a> WriteLn for &'a str {
@@ -54,6 +58,20 @@ impl<'a> WriteLn for &'a str {
 
         json::write_string(w, self)
     }
+    fn write_top<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        write!(w, "{}", self)
+    }
+}
+
+impl WriteLn for String {
+    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        use piston_meta::json;
+
+        json::write_string(w, self)
+    }
+    fn write_top<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        write!(w, "{}", self)
+    }
 }
 
 impl<T: WriteLn> WriteLn for Vec<T> {
@@ -105,6 +123,18 @@ impl<'a, T: WriteLn> WriteLn for &'a T {
     fn write<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
         (*self).write(w)
     }
+    fn write_top<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        (*self).write_top(w)
+    }
+}
+
+impl<'a, T: WriteLn> WriteLn for &'a mut T {
+    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        (**self).write(w)
+    }
+    fn write_top<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        (**self).write_top(w)
+    }
 }
 
 impl<T: WriteLn, A> WriteLn for Secret<T, A> {
@@ -113,6 +143,35 @@ impl<T: WriteLn, A> WriteLn for Secret<T, A> {
     }
 }
 
+impl<T: WriteLn> WriteLn for Result<T, Error> {
+    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match *self {
+            Ok(ref val) => {
+                write!(w, "ok(")?;
+                val.write(w)?;
+            }
+            Err(ref err) => {
+                write!(w, "err(")?;
+                err.message.write(w)?;
+            }
+        }
+        write!(w, ")")
+    }
+}
+
+impl<T: WriteLn> WriteLn for Option<T> {
+    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match *self {
+            Some(ref val) => {
+                write!(w, "some(")?;
+                val.write(w)?;
+                write!(w, ")")
+            }
+            None => write!(w, "none()"),
+        }
+    }
+}
+
 impl WriteLn for Object {
     fn write<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
         use dyon::write::{write_variable, EscapeString};
@@ -142,6 +201,17 @@ impl WriteLn for Object {
     }
 }
 
+impl WriteLn for Link {
+    fn write<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        use dyon::write::{write_variable, EscapeString};
+        use dyon::Runtime;
+
+        let ref rt = Runtime::new();
+        let tabs = 0;
+        write_variable(w, rt, &Variable::Link(Box::new(self.clone())), EscapeString::Json, tabs)
+    }
+}
+
 impl WriteLn for Variable {
     fn write<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
         use dyon::write::{write_variable, EscapeString};
@@ -151,4 +221,12 @@ impl WriteLn for Variable {
         let tabs = 0;
         write_variable(w, rt, self, EscapeString::Json, tabs)
     }
+    fn write_top<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        use dyon::write::{write_variable, EscapeString};
+        use dyon::Runtime;
+
+        let ref rt = Runtime::new();
+        let tabs = 0;
+        write_variable(w, rt, self, EscapeString::None, tabs)
+    }
 }
@@ -0,0 +1,185 @@
+//! Dyon `res` and `opt` values.
+//!
+//! A `res[T]` is mapped to `Result<T, Error>` and `opt[T]` to `Option<T>`.
+
+use std::sync::Arc;
+
+use dyon::{Error, Variable};
+
+use variable::ToVariable;
+use Owned;
+
+/// Creates a successful result.
+pub fn ok<T: Owned>(val: &T) -> Result<T::Output, Error> {
+    Ok(val.owned())
+}
+
+/// Creates an error.
+pub fn err<T, U: ToVariable>(msg: &U) -> Result<T, Error> {
+    Err(Error {message: msg.to_variable(), trace: vec![]})
+}
+
+/// Creates an option with a value.
+pub fn some<T: Owned>(val: &T) -> Option<T::Output> {
+    Some(val.owned())
+}
+
+/// Creates an option without a value.
+pub fn none<T>() -> Option<T> {
+    None
+}
+
+pub fn is_ok<T>(res: &Result<T, Error>) -> bool {
+    res.is_ok()
+}
+
+pub fn is_err<T>(res: &Result<T, Error>) -> bool {
+    res.is_err()
+}
+
+pub fn is_some<T>(opt: &Option<T>) -> bool {
+    opt.is_some()
+}
+
+pub fn is_none<T>(opt: &Option<T>) -> bool {
+    opt.is_none()
+}
+
+/// Formats an error like Dyon does when unwrapping it.
+///
+/// The message is followed by the functions the error was propagated through.
+pub fn error_trace(err: &Error) -> String {
+    use dyon::write::{write_variable, EscapeString};
+    use dyon::Runtime;
+
+    let ref rt = Runtime::new();
+    let mut buf: Vec<u8> = vec![];
+    write_variable(&mut buf, rt, &err.message, EscapeString::None, 0).unwrap();
+    let mut msg = format!("Error:\n{}", String::from_utf8(buf).unwrap());
+    for trace in &err.trace {
+        msg.push_str("\n");
+        msg.push_str(trace);
+    }
+    msg
+}
+
+/// Implemented by values that `?` is used on in a function returning `res`.
+pub trait TryRes {
+    type Output;
+
+    fn try_res(&self, trace: &str) -> Result<Self::Output, Error>;
+}
+
+impl<T: Clone> TryRes for Result<T, Error> {
+    type Output = T;
+
+    fn try_res(&self, trace: &str) -> Result<T, Error> {
+        match *self {
+            Ok(ref val) => Ok(val.clone()),
+            Err(ref err) => {
+                let mut err = err.clone();
+                err.trace.push(trace.into());
+                Err(err)
+            }
+        }
+    }
+}
+
+impl<T: Clone> TryRes for Option<T> {
+    type Output = T;
+
+    fn try_res(&self, trace: &str) -> Result<T, Error> {
+        match *self {
+            Some(ref val) => Ok(val.clone()),
+            None => Err(Error {
+                message: Variable::Text(Arc::new("Expected `some(_)`, found `none()`".into())),
+                trace: vec![trace.into()],
+            }),
+        }
+    }
+}
+
+impl<'a, T: TryRes> TryRes for &'a T {
+    type Output = T::Output;
+
+    fn try_res(&self, trace: &str) -> Result<T::Output, Error> {(**self).try_res(trace)}
+}
+
+/// Converts the operand of `?` into a result, adding the trace of the function.
+pub fn try_res<T: TryRes>(val: &T, trace: &str) -> Result<T::Output, Error> {
+    val.try_res(trace)
+}
+
+pub trait Unwrap {
+    type Output;
+
+    fn unwrap(&self) -> Self::Output;
+}
+
+impl<T: Clone> Unwrap for Result<T, Error> {
+    type Output = T;
+
+    fn unwrap(&self) -> T {
+        match *self {
+            Ok(ref val) => val.clone(),
+            Err(ref err) => panic!("{}", error_trace(err)),
+        }
+    }
+}
+
+impl<T: Clone> Unwrap for Option<T> {
+    type Output = T;
+
+    fn unwrap(&self) -> T {
+        match *self {
+            Some(ref val) => val.clone(),
+            None => panic!("Expected `some(_)`, found `none()`"),
+        }
+    }
+}
+
+/// Returns the value of an `ok` or `some`, or panics with an error trace.
+pub fn unwrap<T: Unwrap>(val: &T) -> T::Output {
+    val.unwrap()
+}
+
+/// Returns the error message, or panics if the result is `ok`.
+pub fn unwrap_err<T>(res: &Result<T, Error>) -> Variable {
+    match *res {
+        Ok(_) => panic!("Expected `err(_)`, found `ok(_)`"),
+        Err(ref err) => err.message.clone(),
+    }
+}
+
+pub trait UnwrapOr {
+    type Output;
+
+    fn unwrap_or(&self, def: &Self::Output) -> Self::Output;
+}
+
+impl<T: Clone> UnwrapOr for Result<T, Error> {
+    type Output = T;
+
+    fn unwrap_or(&self, def: &T) -> T {
+        match *self {
+            Ok(ref val) => val.clone(),
+            Err(_) => def.clone(),
+        }
+    }
+}
+
+impl<T: Clone> UnwrapOr for Option<T> {
+    type Output = T;
+
+    fn unwrap_or(&self, def: &T) -> T {
+        match *self {
+            Some(ref val) => val.clone(),
+            None => def.clone(),
+        }
+    }
+}
+
+/// Returns the value of an `ok` or `some`, or a default value.
+pub fn unwrap_or<T: UnwrapOr>(val: &T, def: &T::Output) -> T::Output {
+    val.unwrap_or(def)
+}
@@ -0,0 +1,71 @@
+//! Text intrinsics.
+//!
+//! Text is returned as `Arc<String>`, like `dyon::Variable::Text`.
+//!
+//! Dyon has no `head`, `tail` or lowercase intrinsics for text,
+//! so there are no helpers for them. `head` and `tail` work on links.
+
+use std::sync::Arc;
+
+use super::WriteLn;
+
+/// Implemented by types that can be used as text arguments.
+pub trait AsText {
+    fn as_text(&self) -> &str;
+}
+
+impl AsText for str {
+    fn as_text(&self) -> &str {self}
+}
+
+impl AsText for String {
+    fn as_text(&self) -> &str {self}
+}
+
+impl AsText for Arc<String> {
+    fn as_text(&self) -> &str {self}
+}
+
+impl<'a, T: AsText + ?Sized> AsText for &'a T {
+    fn as_text(&self) -> &str {(**self).as_text()}
+}
+
+/// Converts a value to text, using the same format as `println`.
+pub fn str<T: WriteLn>(a: &T) -> Arc<String> {
+    let mut buf: Vec<u8> = vec![];
+    a.write_top(&mut buf).unwrap();
+    Arc::new(String::from_utf8(buf).unwrap())
+}
+
+/// Converts text to a JSON string, with quotes and escaped characters.
+pub fn json_string<T: AsText + ?Sized>(a: &T) -> Arc<String> {
+    use piston_meta::json;
+
+    let mut buf: Vec<u8> = vec![];
+    json::write_string(&mut buf, a.as_text()).unwrap();
+    Arc::new(String::from_utf8(buf).unwrap())
+}
+
+/// Returns the characters of text, each as a separate text.
+pub fn chars<T: AsText + ?Sized>(a: &T) -> Vec<Arc<String>> {
+    a.as_text().chars().map(|c| Arc::new(c.to_string())).collect()
+}
+
+pub fn trim<T: AsText + ?Sized>(a: &T) -> Arc<String> {
+    Arc::new(a.as_text().trim().into())
+}
+
+pub fn trim_left<T: AsText + ?Sized>(a: &T) -> Arc<String> {
+    Arc::new(a.as_text().trim_start().into())
+}
+
+pub fn trim_right<T: AsText + ?Sized>(a: &T) -> Arc<String> {
+    Arc::new(a.as_text().trim_end().into())
+}
+
+/// Parses a number, ignoring whitespace around it.
+///
+/// Returns `None` if the text is not a number.
+pub fn parse_number<T: AsText + ?Sized>(a: &T) -> Option<f64> {
+    a.as_text().trim().parse().ok()
+}
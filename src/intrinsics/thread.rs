@@ -0,0 +1,42 @@
+//! Dyon threads created with `go`.
+
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+
+use dyon::{Error, Variable};
+
+/// A thread `thr[T]` created with `go`.
+///
+/// Wraps a `JoinHandle`, because Dyon joins threads by reference.
+pub struct Thread<T> {
+    handle: Mutex<Option<JoinHandle<T>>>,
+}
+
+impl<T> Thread<T> {
+    /// Creates a new thread from a join handle.
+    pub fn new(handle: JoinHandle<T>) -> Thread<T> {
+        Thread {handle: Mutex::new(Some(handle))}
+    }
+}
+
+impl<T> From<JoinHandle<T>> for Thread<T> {
+    fn from(handle: JoinHandle<T>) -> Thread<T> {Thread::new(handle)}
+}
+
+/// Waits for a thread to finish, like `join(thread: _)` in Dyon.
+///
+/// Returns an error if the thread panicked or was already joined.
+#[allow(non_snake_case)]
+pub fn join__thread<T>(thread: &Thread<T>) -> Result<T, Error> {
+    use std::sync::Arc;
+
+    let error = |msg: &str| Error {message: Variable::Text(Arc::new(msg.into())), trace: vec![]};
+    let handle = match thread.handle.lock() {
+        Ok(mut handle) => handle.take(),
+        Err(_) => None,
+    };
+    match handle {
+        Some(handle) => handle.join().map_err(|_| error("Thread did not exit successfully")),
+        None => Err(error("Thread has already been joined")),
+    }
+}
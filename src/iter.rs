@@ -0,0 +1,11 @@
+//! Iteration helper functions.
+
+use std::iter::Cloned;
+use std::slice;
+
+/// Iterates over the items of an array, like `for x in list` in Dyon.
+///
+/// Items are cloned, because Dyon uses copy-on-write.
+pub fn iter<T: Clone>(arr: &Vec<T>) -> Cloned<slice::Iter<T>> {
+    arr.iter().cloned()
+}
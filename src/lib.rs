@@ -72,25 +72,54 @@
 //! and files ending with ".rs" contains the translated code in Rust.
 //!
 //! The source files are checked when typing `cargo test` in the Terminal window.
-//! An error will be reported if there are any character mismatch.
+//! New pairs are discovered automatically.
+//! An error with a diff will be reported if there are any character mismatch.
 //! Therefore, when making changes to the transpiler,
 //! you have to go through all failed cases and check that the code turns out right.
 //!
+//! To update the ".rs" files, type `DYON_TO_RUST_BLESS=1 cargo test`
+//! and review the changes with `git diff`.
+//!
 //! This workflow is very strict, but it helps the confidence that some changes
 //! will not affect translation of existing code too much.
 //!
+//! The "differential" integration test runs each Dyon file in the interpreter,
+//! compiles the Rust file against this crate and checks that both print the same output.
+//! This catches mistakes in the Rust files and the runtime that text comparison does not.
+//! It compiles every Rust file, so it is ignored by default.
+//! Type `cargo test --test differential -- --ignored` to run it.
+//!
 //! There are two special files in the "source" folder:
 //!
 //! 1. "test.dyon" - used to write some test code.
 //! 2. "test.rs" - generated Rust code
 //!
-//! The "tests::test" unit test overwrites "test.rs" by default.
-//! You can change this behavior with a flag in the unit test code.
+//! Run the tests with `DYON_TO_RUST_BLESS=1` to update "test.rs".
 //!
 //! To compile, type `rustc source/test.rs -L target/debug/deps` in the Terminal window.
 //!
 //! To run, type `./test`.
 //!
+//! ### Command line
+//!
+//! The `dyon2rust` binary transpiles Dyon files without writing any Rust code:
+//!
+//! ```text
+//! cargo run --bin dyon2rust -- source/hello_world.dyon -o hello_world.rs
+//! ```
+//!
+//! Multiple files are loaded into the same module.
+//! External functions are registered with `--extern`, e.g.
+//! `--extern "distance(vec4,vec4)->f64=engine::distance"`.
+//! Use `--check` to list all unsupported features in the module,
+//! and `--help` for the other options.
+//!
+//! To transpile scripts when building your crate, see the `build` module.
+//!
+//! Use `CodeSettings::library()` to generate `pub` functions without crate attributes,
+//! such that the code can be called from an existing Rust application.
+//! The path of the runtime crate can be changed with `CodeSettings.runtime`.
+//!
 //! ### Behind the scenes
 //!
 //! The transpiler is really just a huge function generating Rust code (single file) from a Dyon module.
@@ -110,13 +139,15 @@
 //! Variables with overlapping indices are kept in separate scopes using Rust blocks.
 //!
 //! Function calls uses relative indices because Dyon modules can be composed dynamically.
-//! This means that extra testing is required when a module depends on other modules.
+//! The transpiler resolves them relative to the calling function, like Dyon does.
+//! Functions loaded from other files than `main`, e.g. imported by a loader script,
+//! are put in a Rust module named after the file, and called with a path like `lib::foo`.
 //!
 //! ### Functionality
 //!
 //! Currently, due to very early stage, there is no map of supported language features.
 //!
-//! At the moment, the generated Rust code only uses indices.
+//! By default, the generated Rust code only uses indices.
 //!
 //! For example, you will not see the variable names:
 //!
@@ -125,39 +156,116 @@
 //! foo(&mut _0);
 //! ```
 //!
-//! In the future you might be able to tell the code generator
-//! to use variable names from Dyon through a `CodeSettings` struct.
-
-#![feature(specialization)]
+//! To use variable names from Dyon, set `variable_names` in `CodeSettings`
+//! and call `generate_code_with_settings`:
+//!
+//! ```ignore
+//! let mut a = 2.0;
+//! foo(&mut a);
+//! ```
+//!
+//! Current objects are passed as extra arguments to functions using them,
+//! also through functions in between that do not declare them (`current_<name>`).
+//!
+//! Values without type annotations (`any`) are dynamic Dyon variables, `dyon::Variable`.
+//! Arguments are converted with `variable` and `from_variable` when calling functions,
+//! which makes untyped code compile, but runs slower than typed code.
+//!
+//! Functions overloaded by argument mutability get the mutability in the Rust name,
+//! e.g. `foo(mut,_)` becomes `foo_mut_` when there is also a `foo`.
+//!
+//! Calls to external functions, added to the Dyon module from Rust,
+//! require a Rust function registered in `CodeSettings.externals`:
+//!
+//! ```ignore
+//! settings.externals.add("draw", "engine::draw", ArgConvention::Ref);
+//! ```
 
 extern crate dyon;
 extern crate piston_meta;
 
 use dyon::Module;
-use std::io::{self, Write};
+use std::io::Write;
+
+use context::Context;
 
+pub use error::{Diagnostic, SourcePos, TranspileError};
 pub use secret::{Secret, SecretValue};
+pub use settings::CodeSettings;
+pub use external::{ArgConvention, ExternalFn, Externals};
 pub use cond::cond_eval as cond;
 pub use variable::to_variable as variable;
+pub use variable::{FromVariable, ToVariable};
+pub use variable::from_variable;
 pub use assign::set_assign as assign;
+pub use iter::iter;
+pub use owned::{owned, Owned};
 
 pub mod intrinsics;
 pub mod binop;
 pub mod compop;
 pub mod unop;
 pub mod index;
+pub mod build;
 
 mod cond;
+mod context;
+mod current;
+mod error;
+mod external;
+mod infer;
+mod iter;
+mod owned;
 mod secret;
+mod settings;
 mod variable;
 mod assign;
+mod visit;
 
 /// Generates code from a Dyon module.
-pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
+///
+/// Returns an error when the module uses features that are not supported yet.
+pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> Result<(), TranspileError> {
+    generate_code_with_settings(w, module, &CodeSettings::new())
+}
+
+/// Generates code from a Dyon module using settings.
+pub fn generate_code_with_settings<W: Write>(
+    w: &mut W,
+    module: &Module,
+    settings: &CodeSettings
+) -> Result<(), TranspileError> {
+    let ref cx = Context::new(module, settings);
+    generate_module(w, cx)
+}
+
+/// Checks a Dyon module for features that are not supported yet.
+///
+/// Walks every function like `generate_code`, but continues after errors.
+/// Returns an empty list if the whole module can be transpiled.
+pub fn check_module(module: &Module) -> Vec<Diagnostic> {
+    check_module_with_settings(module, &CodeSettings::new())
+}
+
+/// Checks a Dyon module for features that are not supported yet using settings.
+///
+/// Use this when external functions are registered in the settings.
+pub fn check_module_with_settings(module: &Module, settings: &CodeSettings) -> Vec<Diagnostic> {
+    use std::io::sink;
+
+    let ref cx = Context::checker(module, settings);
+    // Errors are recorded as diagnostics, so only I/O errors can happen here.
+    let _ = generate_module(&mut sink(), cx);
+    cx.diagnostics()
+}
+
+fn generate_module<W: Write>(w: &mut W, cx: &Context) -> Result<(), TranspileError> {
     use dyon::ast::*;
     use dyon::ty::Type;
+    use piston_meta::Range;
+    use std::sync::Arc;
 
-    fn generate_tabs<W: Write>(w: &mut W, tabs: u16) -> io::Result<()> {
+    fn generate_tabs<W: Write>(w: &mut W, tabs: u16) -> Result<(), TranspileError> {
         for _ in 0..4 * tabs {
             write!(w, " ")?;
         }
@@ -165,33 +273,88 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         Ok(())
     }
 
-    fn generate_call<W: Write>(
-        w: &mut W,
-        tabs: u16,
-        stack_len: usize,
-        call: &Call,
-        module: &Module
-    ) -> io::Result<()> {
-        use dyon::FnIndex;
-
-        if &*call.name == "where" {
-            write!(w, "{}_(", call.name)?;
-        } else {
-            if let Some(ind) = call.name.find('(') {
-                write!(w, "{}(", &call.name[..ind])?;
-            } else {
-                write!(w, "{}(", call.name)?;
-            }
+    /// Returns the name of the called function without mutability information.
+    fn call_name(call: &Call) -> &str {
+        match call.name.find('(') {
+            Some(ind) => &call.name[..ind],
+            None => &call.name[..],
         }
+    }
+
+    /// A called function and how its arguments are passed, see `call_target`.
+    struct CallTarget<'a> {
+        /// The Rust path of the function.
+        path: String,
+        /// The function, when it is loaded from Dyon.
+        callee: Option<&'a Function>,
+        /// How arguments are passed, for external functions.
+        args: ArgConvention,
+        /// Arguments declared `mut` in the call.
+        mutable_args: Vec<usize>,
+        /// The item argument of an array intrinsic, when the array holds dynamic variables.
+        dynamic_item: Option<usize>,
+        /// Whether the function returns a value, which is put on the stack before arguments.
+        return_var: usize,
+    }
+
+    /// Resolves the function of a call, reporting unsupported functions.
+    fn call_target<'a>(call: &Call, cx: &'a Context) -> Result<CallTarget<'a>, TranspileError> {
+        use dyon::FnIndex;
 
+        let mut name = call_name(call);
+        let mut external = None;
+        let mut callee = None;
+        let mut path = String::new();
         let return_var = match call.f_index.get() {
             FnIndex::Loaded(f_index) => {
-                // TODO: Should this be computed relative somehow?
-                let new_index = f_index as usize;
-                if module.functions[new_index].returns() {1} else {0}
+                let new_index = cx.loaded_function(f_index);
+                callee = Some(&cx.module.functions[new_index]);
+                name = &cx.rust_names[new_index];
+                path = cx.function_path(new_index);
+                if cx.module.functions[new_index].returns() {1} else {0}
+            }
+            FnIndex::Intrinsic(_) => {
+                if !intrinsics::is_supported(name) {
+                    cx.report(TranspileError::UnsupportedIntrinsic {
+                        name: call.name.clone(),
+                        pos: SourcePos::new(call.source_range),
+                    })?;
+                }
+                0
+            }
+            FnIndex::None => {
+                cx.report(TranspileError::UnresolvedCall {
+                    name: call.name.clone(),
+                    pos: SourcePos::new(call.source_range),
+                })?;
+                0
             }
-            // TODO: Check other cases.
-            _ => 0
+            FnIndex::External(_) => {
+                external = cx.settings.externals.get(name);
+                if external.is_none() {
+                    cx.report(TranspileError::UnregisteredExternal {
+                        name: call.name.clone(),
+                        pos: SourcePos::new(call.source_range),
+                    })?;
+                }
+                0
+            }
+        };
+        let path = if let Some(external) = external {
+            external.path.clone()
+        } else if name == "where" {
+            format!("{}_", name)
+        } else {
+            format!("{}{}", path, name)
+        };
+        // Items added to arrays of dynamic variables are converted into variables.
+        let dynamic_item = if let FnIndex::Intrinsic(_) = call.f_index.get() {
+            match (array_item_arg(name), call.args.first().and_then(|arr| cx.types.get(arr))) {
+                (Some(ind), Some(&Type::Array(ref ty))) if **ty == Type::Any => Some(ind),
+                _ => None,
+            }
+        } else {
+            None
         };
         let mut mutable_args = vec![];
         if let Some(ind) = call.name.find('(') {
@@ -201,28 +364,176 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
                 if arg == "mut" {mutable_args.push(i)};
             }
         }
-        let n = call.args.len();
-        for (i, exp) in call.args.iter().enumerate() {
-            let mutable = mutable_args.iter().any(|&j| j == i);
-            if mutable {
-                write!(w, "&mut ")?;
+        Ok(CallTarget {
+            path: path,
+            callee: callee,
+            args: external.map(|f| f.args).unwrap_or(ArgConvention::Ref),
+            mutable_args: mutable_args,
+            dynamic_item: dynamic_item,
+            return_var: return_var,
+        })
+    }
+
+    /// Returns the code written before and after an argument of a call.
+    ///
+    /// Converts between dynamic variables and other types.
+    fn arg_wrapper(
+        target: &CallTarget,
+        i: usize,
+        exp: &Expression,
+        cx: &Context
+    ) -> (&'static str, &'static str) {
+        if target.mutable_args.iter().any(|&j| j == i) {
+            ("&mut ", "")
+        } else if let Some(callee) = target.callee {
+            let arg_ty = &callee.args[i].ty;
+            if cx.types.is_variable(exp) {
+                if convert_from_variable(arg_ty) {("&from_variable(&", ")")} else {("&", "")}
+            } else if let Type::Any = *arg_ty {
+                ("&variable(&", ")")
             } else {
-                write!(w, "&")?;
+                ("&", "")
             }
-            generate_expression(w, tabs, stack_len + return_var, exp, module)?;
+        } else if target.dynamic_item == Some(i) && !cx.types.is_variable(exp) {
+            ("&variable(&", ")")
+        } else {
+            match target.args {
+                ArgConvention::Ref => ("&", ""),
+                ArgConvention::Value => ("owned(&", ")"),
+                ArgConvention::Variable => ("&variable(&", ")"),
+            }
+        }
+    }
+
+    fn generate_call<W: Write>(
+        w: &mut W,
+        tabs: u16,
+        stack_len: usize,
+        call: &Call,
+        cx: &Context
+    ) -> Result<(), TranspileError> {
+        use dyon::FnIndex;
+
+        let target = call_target(call, cx)?;
+        write!(w, "{}(", target.path)?;
+        let n = call.args.len();
+        for (i, exp) in call.args.iter().enumerate() {
+            let (before, after) = arg_wrapper(&target, i, exp, cx);
+            write!(w, "{}", before)?;
+            generate_expression(w, tabs, stack_len + target.return_var, exp, cx)?;
+            write!(w, "{}", after)?;
             if (i + 1) != n {
                 write!(w, ", ")?;
             }
         }
+        // Current objects are passed as extra arguments.
+        if let FnIndex::Loaded(f_index) = call.f_index.get() {
+            let f_index = cx.loaded_function(f_index);
+            for (i, name) in cx.currents.needs[f_index].iter().enumerate() {
+                if n > 0 || i > 0 {
+                    write!(w, ", ")?;
+                }
+                write!(w, "{}", cx.pass_current(name, cx.currents.is_mutable(f_index, name)))?;
+            }
+        }
         write!(w, ")")?;
 
         Ok(())
     }
 
-    fn generate_text<W: Write>(w: &mut W, text: &Text) -> io::Result<()> {
+    /// Returns the argument index of the item added by an array intrinsic.
+    fn array_item_arg(name: &str) -> Option<usize> {
+        match name {
+            "push" | "push_ref" => Some(1),
+            "insert" => Some(2),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if a dynamic variable can be converted into the argument type.
+    fn convert_from_variable(ty: &Type) -> bool {
+        match *ty {
+            // Closures are passed as `&Fn`, which can not be converted.
+            Type::Any | Type::Closure(_) => false,
+            _ => true,
+        }
+    }
+
+    /// Generates code for a `go` expression.
+    ///
+    /// The arguments are evaluated before spawning the thread,
+    /// such that owned values can be moved into the thread.
+    /// Arguments are converted like in `generate_call` when calling the function.
+    /// The thread gets a copy of the current objects used by the function.
+    fn generate_go<W: Write>(
+        w: &mut W,
+        tabs: u16,
+        stack_len: usize,
+        go: &Go,
+        cx: &Context
+    ) -> Result<(), TranspileError> {
+        use dyon::FnIndex;
+
+        let target = call_target(&go.call, cx)?;
+        let f_index = match go.call.f_index.get() {
+            FnIndex::Loaded(f_index) => Some(cx.loaded_function(f_index)),
+            _ => None,
+        };
+        let currents: &[Arc<String>] = match f_index {
+            Some(f_index) => &cx.currents.needs[f_index],
+            None => &[],
+        };
+        let mutable = |name: &Arc<String>| match f_index {
+            Some(f_index) => cx.currents.is_mutable(f_index, name),
+            None => false,
+        };
+
+        writeln!(w, "{{")?;
+        let n = go.call.args.len();
+        for (i, exp) in go.call.args.iter().enumerate() {
+            generate_tabs(w, tabs + 1)?;
+            let mutable = target.mutable_args.iter().any(|&j| j == i);
+            write!(w, "let {}_{} = owned(&", if mutable {"mut "} else {""}, stack_len + i)?;
+            generate_expression(w, tabs + 1, stack_len + target.return_var, exp, cx)?;
+            writeln!(w, ");")?;
+        }
+        for (i, name) in currents.iter().enumerate() {
+            generate_tabs(w, tabs + 1)?;
+            writeln!(w, "let {}_{} = owned({});", if mutable(name) {"mut "} else {""},
+                stack_len + n + i, cx.pass_current(name, false))?;
+        }
+        generate_tabs(w, tabs + 1)?;
+        write!(w, "Thread::new(::std::thread::spawn(move || {}(", target.path)?;
+        for (i, exp) in go.call.args.iter().enumerate() {
+            let (before, after) = arg_wrapper(&target, i, exp, cx);
+            write!(w, "{}_{}{}", before, stack_len + i, after)?;
+            if (i + 1) != n {
+                write!(w, ", ")?;
+            }
+        }
+        for (i, name) in currents.iter().enumerate() {
+            if n > 0 || i > 0 {
+                write!(w, ", ")?;
+            }
+            write!(w, "{}_{}", if mutable(name) {"&mut "} else {"&"}, stack_len + n + i)?;
+        }
+        writeln!(w, ")))")?;
+        generate_tabs(w, tabs)?;
+        write!(w, "}}")?;
+
+        Ok(())
+    }
+
+    /// Generates code for a text literal.
+    ///
+    /// Text is owned, such that computed text can be stored and returned in the same way.
+    fn generate_text<W: Write>(w: &mut W, text: &Text) -> Result<(), TranspileError> {
         use piston_meta::json;
 
-        json::write_string(w, &text.text)
+        write!(w, "Arc::new(String::from(")?;
+        json::write_string(w, &text.text)?;
+        write!(w, "))")?;
+        Ok(())
     }
 
     fn generate_for<W: Write>(
@@ -230,9 +541,9 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         tabs: u16,
         stack_len: usize,
         for_expr: &For,
-        module: &Module
-    ) -> io::Result<()> {
-        generate_expression(w, tabs, stack_len, &for_expr.init, module)?;
+        cx: &Context
+    ) -> Result<(), TranspileError> {
+        generate_expression(w, tabs, stack_len, &for_expr.init, cx)?;
         writeln!(w, ";")?;
         generate_tabs(w, tabs)?;
         if let Some(ref label) = for_expr.label {
@@ -242,16 +553,16 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         }
         generate_tabs(w, tabs + 1)?;
         write!(w, "if !cond(&")?;
-        generate_expression(w, tabs + 2, stack_len + 1, &for_expr.cond, module)?;
+        generate_expression(w, tabs + 2, stack_len + 1, &for_expr.cond, cx)?;
         writeln!(w, ") {{break}};")?;
         generate_tabs(w, tabs + 1)?;
         writeln!(w, "{{")?;
-        generate_block(w, tabs + 2, stack_len + 1, &for_expr.block, module)?;
+        generate_block(w, tabs + 2, stack_len + 1, &for_expr.block, cx)?;
         writeln!(w, ";")?;
         generate_tabs(w, tabs + 1)?;
         writeln!(w, "}}")?;
         generate_tabs(w, tabs + 1)?;
-        generate_expression(w, tabs + 1, stack_len, &for_expr.step, module)?;
+        generate_expression(w, tabs + 1, stack_len, &for_expr.step, cx)?;
         writeln!(w, ";")?;
         generate_tabs(w, tabs)?;
         write!(w, "}}")?;
@@ -263,12 +574,12 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         tabs: u16,
         stack_len: usize,
         for_n: &ForN,
-        module: &Module
-    ) -> io::Result<()> {
-        let id = stack_len;
-        write!(w, "let mut _{}: f64 = ", id)?;
+        cx: &Context
+    ) -> Result<(), TranspileError> {
+        let id = cx.declare(stack_len, &for_n.name);
+        write!(w, "let mut {}: f64 = ", id)?;
         if let Some(ref exp) = for_n.start {
-            generate_expression(w, tabs, stack_len, exp, module)?;
+            generate_f64(w, tabs, stack_len, exp, cx)?;
         } else {
             write!(w, "0.0")?;
         }
@@ -277,7 +588,7 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         let n_id = stack_len + 1;
         generate_tabs(w, tabs)?;
         write!(w, "let _{}: f64 = ", n_id)?;
-        generate_expression(w, tabs, stack_len, &for_n.end, module)?;
+        generate_f64(w, tabs, stack_len, &for_n.end, cx)?;
         writeln!(w, ";")?;
 
         generate_tabs(w, tabs)?;
@@ -288,17 +599,17 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         }
 
         generate_tabs(w, tabs + 1)?;
-        writeln!(w, "if _{} >= _{} {{break}};", id, n_id)?;
+        writeln!(w, "if {} >= _{} {{break}};", id, n_id)?;
 
         generate_tabs(w, tabs + 1)?;
         writeln!(w, "{{")?;
-        generate_block(w, tabs + 2, stack_len + 1, &for_n.block, module)?;
+        generate_block(w, tabs + 2, stack_len + 1, &for_n.block, cx)?;
         writeln!(w, ";")?;
         generate_tabs(w, tabs + 1)?;
         writeln!(w, "}}")?;
 
         generate_tabs(w, tabs + 1)?;
-        writeln!(w, "_{} += 1.0;", id)?;
+        writeln!(w, "{} += 1.0;", id)?;
 
         generate_tabs(w, tabs)?;
         write!(w, "}}")?;
@@ -311,15 +622,15 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         tabs: u16,
         stack_len: usize,
         for_n: &ForN,
-        module: &Module
-    ) -> io::Result<()> {
+        cx: &Context
+    ) -> Result<(), TranspileError> {
         writeln!(w, "{{")?;
 
-        let id = stack_len;
+        let id = cx.declare(stack_len, &for_n.name);
         generate_tabs(w, tabs + 1)?;
-        write!(w, "let mut _{}: f64 = ", id)?;
+        write!(w, "let mut {}: f64 = ", id)?;
         if let Some(ref exp) = for_n.start {
-            generate_expression(w, tabs, stack_len, exp, module)?;
+            generate_f64(w, tabs, stack_len, exp, cx)?;
         } else {
             write!(w, "0.0")?;
         }
@@ -332,18 +643,18 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         let n_id = stack_len + 2;
         generate_tabs(w, tabs + 1)?;
         write!(w, "let _{}: f64 = ", n_id)?;
-        generate_expression(w, tabs, stack_len, &for_n.end, module)?;
+        generate_f64(w, tabs, stack_len, &for_n.end, cx)?;
         writeln!(w, ";")?;
 
         generate_tabs(w, tabs + 1)?;
         writeln!(w, "loop {{")?;
 
         generate_tabs(w, tabs + 2)?;
-        writeln!(w, "if _{} >= _{} {{break}};", id, n_id)?;
+        writeln!(w, "if {} >= _{} {{break}};", id, n_id)?;
 
         generate_tabs(w, tabs + 2)?;
         writeln!(w, "_{} &= {{", all_id)?;
-        generate_block(w, tabs + 3, stack_len + 1, &for_n.block, module)?;
+        generate_block(w, tabs + 3, stack_len + 1, &for_n.block, cx)?;
         writeln!(w, "")?;
         generate_tabs(w, tabs + 2)?;
         writeln!(w, "}};")?;
@@ -351,14 +662,14 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         generate_tabs(w, tabs + 2)?;
         writeln!(w, "if !cond(&_{}) {{", all_id)?;
         generate_tabs(w, tabs + 3)?;
-        writeln!(w, "_{}.secret.push(_{});", all_id, id)?;
+        writeln!(w, "_{}.secret.push({});", all_id, id)?;
         generate_tabs(w, tabs + 3)?;
         writeln!(w, "break;")?;
         generate_tabs(w, tabs + 2)?;
         writeln!(w, "}}")?;
 
         generate_tabs(w, tabs + 2)?;
-        writeln!(w, "_{} += 1.0;", id)?;
+        writeln!(w, "{} += 1.0;", id)?;
 
         generate_tabs(w, tabs + 1)?;
         writeln!(w, "}}")?;
@@ -376,15 +687,15 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         tabs: u16,
         stack_len: usize,
         for_n: &ForN,
-        module: &Module
-    ) -> io::Result<()> {
+        cx: &Context
+    ) -> Result<(), TranspileError> {
         writeln!(w, "{{")?;
 
-        let id = stack_len;
+        let id = cx.declare(stack_len, &for_n.name);
         generate_tabs(w, tabs + 1)?;
-        write!(w, "let mut _{}: f64 = ", id)?;
+        write!(w, "let mut {}: f64 = ", id)?;
         if let Some(ref exp) = for_n.start {
-            generate_expression(w, tabs, stack_len, exp, module)?;
+            generate_f64(w, tabs, stack_len, exp, cx)?;
         } else {
             write!(w, "0.0")?;
         }
@@ -397,18 +708,18 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         let n_id = stack_len + 2;
         generate_tabs(w, tabs + 1)?;
         write!(w, "let _{}: f64 = ", n_id)?;
-        generate_expression(w, tabs, stack_len, &for_n.end, module)?;
+        generate_f64(w, tabs, stack_len, &for_n.end, cx)?;
         writeln!(w, ";")?;
 
         generate_tabs(w, tabs + 1)?;
         writeln!(w, "loop {{")?;
 
         generate_tabs(w, tabs + 2)?;
-        writeln!(w, "if _{} >= _{} {{break}};", id, n_id)?;
+        writeln!(w, "if {} >= _{} {{break}};", id, n_id)?;
 
         generate_tabs(w, tabs + 2)?;
         writeln!(w, "_{} |= {{", any_id)?;
-        generate_block(w, tabs + 3, stack_len + 1, &for_n.block, module)?;
+        generate_block(w, tabs + 3, stack_len + 1, &for_n.block, cx)?;
         writeln!(w, "")?;
         generate_tabs(w, tabs + 2)?;
         writeln!(w, "}};")?;
@@ -416,14 +727,14 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         generate_tabs(w, tabs + 2)?;
         writeln!(w, "if cond(&_{}) {{", any_id)?;
         generate_tabs(w, tabs + 3)?;
-        writeln!(w, "_{}.secret.push(_{});", any_id, id)?;
+        writeln!(w, "_{}.secret.push({});", any_id, id)?;
         generate_tabs(w, tabs + 3)?;
         writeln!(w, "break;")?;
         generate_tabs(w, tabs + 2)?;
         writeln!(w, "}}")?;
 
         generate_tabs(w, tabs + 2)?;
-        writeln!(w, "_{} += 1.0;", id)?;
+        writeln!(w, "{} += 1.0;", id)?;
 
         generate_tabs(w, tabs + 1)?;
         writeln!(w, "}}")?;
@@ -441,15 +752,15 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         tabs: u16,
         stack_len: usize,
         for_n: &ForN,
-        module: &Module
-    ) -> io::Result<()> {
+        cx: &Context
+    ) -> Result<(), TranspileError> {
         writeln!(w, "{{")?;
 
-        let id = stack_len;
+        let id = cx.declare(stack_len, &for_n.name);
         generate_tabs(w, tabs + 1)?;
-        write!(w, "let mut _{}: f64 = ", id)?;
+        write!(w, "let mut {}: f64 = ", id)?;
         if let Some(ref exp) = for_n.start {
-            generate_expression(w, tabs, stack_len, exp, module)?;
+            generate_f64(w, tabs, stack_len, exp, cx)?;
         } else {
             write!(w, "0.0")?;
         }
@@ -463,19 +774,19 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         writeln!(w, "loop {{")?;
 
         generate_tabs(w, tabs + 2)?;
-        write!(w, "if _{} >= ", id)?;
-        generate_expression(w, tabs, stack_len, &for_n.end, module)?;
+        write!(w, "if {} >= ", id)?;
+        generate_f64(w, tabs, stack_len, &for_n.end, cx)?;
         writeln!(w, " {{break}};")?;
 
         generate_tabs(w, tabs + 2)?;
         writeln!(w, "_{} += {{", sum_id)?;
-        generate_block(w, tabs + 3, stack_len + 1, &for_n.block, module)?;
+        generate_block(w, tabs + 3, stack_len + 1, &for_n.block, cx)?;
         writeln!(w, "")?;
         generate_tabs(w, tabs + 2)?;
         writeln!(w, "}};")?;
 
         generate_tabs(w, tabs + 2)?;
-        writeln!(w, "_{} += 1.0;", id)?;
+        writeln!(w, "{} += 1.0;", id)?;
 
         generate_tabs(w, tabs + 1)?;
         writeln!(w, "}}")?;
@@ -493,15 +804,15 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         tabs: u16,
         stack_len: usize,
         for_n: &ForN,
-        module: &Module
-    ) -> io::Result<()> {
+        cx: &Context
+    ) -> Result<(), TranspileError> {
         writeln!(w, "{{")?;
 
-        let id = stack_len;
+        let id = cx.declare(stack_len, &for_n.name);
         generate_tabs(w, tabs + 1)?;
-        write!(w, "let mut _{}: f64 = ", id)?;
+        write!(w, "let mut {}: f64 = ", id)?;
         if let Some(ref exp) = for_n.start {
-            generate_expression(w, tabs, stack_len, exp, module)?;
+            generate_f64(w, tabs, stack_len, exp, cx)?;
         } else {
             write!(w, "0.0")?;
         }
@@ -515,19 +826,19 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         writeln!(w, "loop {{")?;
 
         generate_tabs(w, tabs + 2)?;
-        write!(w, "if _{} >= ", id)?;
-        generate_expression(w, tabs, stack_len, &for_n.end, module)?;
+        write!(w, "if {} >= ", id)?;
+        generate_f64(w, tabs, stack_len, &for_n.end, cx)?;
         writeln!(w, " {{break}};")?;
 
         generate_tabs(w, tabs + 2)?;
         writeln!(w, "_{} *= {{", prod_id)?;
-        generate_block(w, tabs + 3, stack_len + 1, &for_n.block, module)?;
+        generate_block(w, tabs + 3, stack_len + 1, &for_n.block, cx)?;
         writeln!(w, "")?;
         generate_tabs(w, tabs + 2)?;
         writeln!(w, "}};")?;
 
         generate_tabs(w, tabs + 2)?;
-        writeln!(w, "_{} += 1.0;", id)?;
+        writeln!(w, "{} += 1.0;", id)?;
 
         generate_tabs(w, tabs + 1)?;
         writeln!(w, "}}")?;
@@ -545,15 +856,15 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         tabs: u16,
         stack_len: usize,
         for_n: &ForN,
-        module: &Module
-    ) -> io::Result<()> {
+        cx: &Context
+    ) -> Result<(), TranspileError> {
         writeln!(w, "{{")?;
 
-        let id = stack_len;
+        let id = cx.declare(stack_len, &for_n.name);
         generate_tabs(w, tabs + 1)?;
-        write!(w, "let mut _{}: f64 = ", id)?;
+        write!(w, "let mut {}: f64 = ", id)?;
         if let Some(ref exp) = for_n.start {
-            generate_expression(w, tabs, stack_len, exp, module)?;
+            generate_f64(w, tabs, stack_len, exp, cx)?;
         } else {
             write!(w, "0.0")?;
         }
@@ -570,19 +881,19 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         let n_id = stack_len + 3;
         generate_tabs(w, tabs + 1)?;
         write!(w, "let _{}: f64 = ", n_id)?;
-        generate_expression(w, tabs, stack_len, &for_n.end, module)?;
+        generate_f64(w, tabs, stack_len, &for_n.end, cx)?;
         writeln!(w, ";")?;
 
         generate_tabs(w, tabs + 1)?;
         writeln!(w, "loop {{")?;
 
         generate_tabs(w, tabs + 2)?;
-        writeln!(w, "if _{} >= _{} {{break}};", id, n_id)?;
+        writeln!(w, "if {} >= _{} {{break}};", id, n_id)?;
 
         generate_tabs(w, tabs + 2)?;
         let res_id = stack_len + 3;
         writeln!(w, "let _{} = {{", res_id)?;
-        generate_block(w, tabs + 3, stack_len + 1, &for_n.block, module)?;
+        generate_block(w, tabs + 3, stack_len + 1, &for_n.block, cx)?;
         writeln!(w, "")?;
         generate_tabs(w, tabs + 2)?;
         writeln!(w, "}};")?;
@@ -591,20 +902,20 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         generate_tabs(w, tabs + 3)?;
         writeln!(w, "_{} = _{}.into();", max_id, res_id)?;
         generate_tabs(w, tabs + 3)?;
-        writeln!(w, "_{} = Some(_{});", track_id, id)?;
+        writeln!(w, "_{} = Some({});", track_id, id)?;
         generate_tabs(w, tabs + 2)?;
         writeln!(w, "}}")?;
 
         generate_tabs(w, tabs + 2)?;
-        writeln!(w, "_{} += 1.0;", id)?;
+        writeln!(w, "{} += 1.0;", id)?;
 
         generate_tabs(w, tabs + 1)?;
         writeln!(w, "}}")?;
 
         generate_tabs(w, tabs + 1)?;
-        writeln!(w, "if let Some(_{}) = _{} {{", id, track_id)?;
+        writeln!(w, "if let Some({}) = _{} {{", id, track_id)?;
         generate_tabs(w, tabs + 2)?;
-        writeln!(w, "_{}.secret.push(_{});", max_id, id)?;
+        writeln!(w, "_{}.secret.push({});", max_id, id)?;
         generate_tabs(w, tabs + 1)?;
         writeln!(w, "}}")?;
         generate_tabs(w, tabs + 1)?;
@@ -620,15 +931,15 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         tabs: u16,
         stack_len: usize,
         for_n: &ForN,
-        module: &Module
-    ) -> io::Result<()> {
+        cx: &Context
+    ) -> Result<(), TranspileError> {
         writeln!(w, "{{")?;
 
-        let id = stack_len;
+        let id = cx.declare(stack_len, &for_n.name);
         generate_tabs(w, tabs + 1)?;
-        write!(w, "let mut _{}: f64 = ", id)?;
+        write!(w, "let mut {}: f64 = ", id)?;
         if let Some(ref exp) = for_n.start {
-            generate_expression(w, tabs, stack_len, exp, module)?;
+            generate_f64(w, tabs, stack_len, exp, cx)?;
         } else {
             write!(w, "0.0")?;
         }
@@ -645,19 +956,19 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         let n_id = stack_len + 3;
         generate_tabs(w, tabs + 1)?;
         write!(w, "let _{}: f64 = ", n_id)?;
-        generate_expression(w, tabs, stack_len, &for_n.end, module)?;
+        generate_f64(w, tabs, stack_len, &for_n.end, cx)?;
         writeln!(w, ";")?;
 
         generate_tabs(w, tabs + 1)?;
         writeln!(w, "loop {{")?;
 
         generate_tabs(w, tabs + 2)?;
-        writeln!(w, "if _{} >= _{} {{break}};", id, n_id)?;
+        writeln!(w, "if {} >= _{} {{break}};", id, n_id)?;
 
         generate_tabs(w, tabs + 2)?;
         let res_id = stack_len + 3;
         writeln!(w, "let _{} = {{", res_id)?;
-        generate_block(w, tabs + 3, stack_len + 1, &for_n.block, module)?;
+        generate_block(w, tabs + 3, stack_len + 1, &for_n.block, cx)?;
         generate_tabs(w, tabs + 2)?;
         writeln!(w, "}};")?;
         generate_tabs(w, tabs + 2)?;
@@ -665,20 +976,20 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         generate_tabs(w, tabs + 3)?;
         writeln!(w, "_{} = _{}.into();", min_id, res_id)?;
         generate_tabs(w, tabs + 3)?;
-        writeln!(w, "_{} = Some(_{});", track_id, id)?;
+        writeln!(w, "_{} = Some({});", track_id, id)?;
         generate_tabs(w, tabs + 2)?;
         writeln!(w, "}}")?;
 
         generate_tabs(w, tabs + 2)?;
-        writeln!(w, "_{} += 1.0;", id)?;
+        writeln!(w, "{} += 1.0;", id)?;
 
         generate_tabs(w, tabs + 1)?;
         writeln!(w, "}}")?;
 
         generate_tabs(w, tabs + 1)?;
-        writeln!(w, "if let Some(_{}) = _{} {{", id, track_id)?;
+        writeln!(w, "if let Some({}) = _{} {{", id, track_id)?;
         generate_tabs(w, tabs + 2)?;
-        writeln!(w, "_{}.secret.push(_{});", min_id, id)?;
+        writeln!(w, "_{}.secret.push({});", min_id, id)?;
         generate_tabs(w, tabs + 1)?;
         writeln!(w, "}}")?;
         generate_tabs(w, tabs + 1)?;
@@ -694,15 +1005,15 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         tabs: u16,
         stack_len: usize,
         for_n: &ForN,
-        module: &Module
-    ) -> io::Result<()> {
+        cx: &Context
+    ) -> Result<(), TranspileError> {
         writeln!(w, "{{")?;
 
-        let id = stack_len;
+        let id = cx.declare(stack_len, &for_n.name);
         generate_tabs(w, tabs + 1)?;
-        write!(w, "let mut _{}: f64 = ", id)?;
+        write!(w, "let mut {}: f64 = ", id)?;
         if let Some(ref exp) = for_n.start {
-            generate_expression(w, tabs, stack_len, exp, module)?;
+            generate_f64(w, tabs, stack_len, exp, cx)?;
         } else {
             write!(w, "0.0")?;
         }
@@ -716,23 +1027,361 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         writeln!(w, "loop {{")?;
 
         generate_tabs(w, tabs + 2)?;
-        write!(w, "if _{} >= ", id)?;
-        generate_expression(w, tabs, stack_len, &for_n.end, module)?;
+        write!(w, "if {} >= ", id)?;
+        generate_f64(w, tabs, stack_len, &for_n.end, cx)?;
         writeln!(w, " {{break}};")?;
 
         generate_tabs(w, tabs + 2)?;
         let res_id = stack_len + 3;
         writeln!(w, "let _{} = {{", res_id)?;
-        generate_block(w, tabs + 3, stack_len + 1, &for_n.block, module)?;
+        generate_block(w, tabs + 3, stack_len + 1, &for_n.block, cx)?;
+        writeln!(w, "")?;
+        generate_tabs(w, tabs + 2)?;
+        writeln!(w, "}};")?;
+        generate_tabs(w, tabs + 2)?;
+        writeln!(w, "_{}.push(_{});", sift_id, res_id)?;
+
+        generate_tabs(w, tabs + 2)?;
+        writeln!(w, "{} += 1.0;", id)?;
+
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "}}")?;
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "_{}", sift_id)?;
+        generate_tabs(w, tabs)?;
+        write!(w, "}}")?;
+        Ok(())
+    }
+
+    fn generate_for_in<W: Write>(
+        w: &mut W,
+        tabs: u16,
+        stack_len: usize,
+        for_in: &ForIn,
+        cx: &Context
+    ) -> Result<(), TranspileError> {
+        let id = cx.declare(stack_len, &for_in.name);
+        if let Some(ref label) = for_in.label {
+            write!(w, "'{}: ", label)?;
+        }
+        write!(w, "for {} in iter(&", id)?;
+        generate_expression(w, tabs, stack_len, &for_in.iter, cx)?;
+        writeln!(w, ") {{")?;
+        generate_block(w, tabs + 1, stack_len + 1, &for_in.block, cx)?;
+        writeln!(w, ";")?;
+        generate_tabs(w, tabs)?;
+        write!(w, "}}")?;
+
+        Ok(())
+    }
+
+    fn generate_all_in<W: Write>(
+        w: &mut W,
+        tabs: u16,
+        stack_len: usize,
+        for_in: &ForIn,
+        cx: &Context
+    ) -> Result<(), TranspileError> {
+        writeln!(w, "{{")?;
+
+        let id = cx.declare(stack_len, &for_in.name);
+        let all_id = stack_len + 1;
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "let mut _{}: Secret<bool, _> = Secret::new_bool(true);", all_id)?;
+
+        generate_tabs(w, tabs + 1)?;
+        write!(w, "for {} in iter(&", id)?;
+        generate_expression(w, tabs, stack_len, &for_in.iter, cx)?;
+        writeln!(w, ") {{")?;
+
+        generate_tabs(w, tabs + 2)?;
+        writeln!(w, "_{} &= {{", all_id)?;
+        generate_block(w, tabs + 3, stack_len + 1, &for_in.block, cx)?;
+        writeln!(w, "")?;
+        generate_tabs(w, tabs + 2)?;
+        writeln!(w, "}};")?;
+
+        generate_tabs(w, tabs + 2)?;
+        writeln!(w, "if !cond(&_{}) {{", all_id)?;
+        generate_tabs(w, tabs + 3)?;
+        writeln!(w, "_{}.secret.push({});", all_id, id)?;
+        generate_tabs(w, tabs + 3)?;
+        writeln!(w, "break;")?;
+        generate_tabs(w, tabs + 2)?;
+        writeln!(w, "}}")?;
+
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "}}")?;
+
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "_{}", all_id)?;
+        generate_tabs(w, tabs)?;
+        write!(w, "}}")?;
+
+        Ok(())
+    }
+
+    fn generate_any_in<W: Write>(
+        w: &mut W,
+        tabs: u16,
+        stack_len: usize,
+        for_in: &ForIn,
+        cx: &Context
+    ) -> Result<(), TranspileError> {
+        writeln!(w, "{{")?;
+
+        let id = cx.declare(stack_len, &for_in.name);
+        let any_id = stack_len + 1;
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "let mut _{}: Secret<bool, _> = Secret::new_bool(false);", any_id)?;
+
+        generate_tabs(w, tabs + 1)?;
+        write!(w, "for {} in iter(&", id)?;
+        generate_expression(w, tabs, stack_len, &for_in.iter, cx)?;
+        writeln!(w, ") {{")?;
+
+        generate_tabs(w, tabs + 2)?;
+        writeln!(w, "_{} |= {{", any_id)?;
+        generate_block(w, tabs + 3, stack_len + 1, &for_in.block, cx)?;
+        writeln!(w, "")?;
+        generate_tabs(w, tabs + 2)?;
+        writeln!(w, "}};")?;
+
+        generate_tabs(w, tabs + 2)?;
+        writeln!(w, "if cond(&_{}) {{", any_id)?;
+        generate_tabs(w, tabs + 3)?;
+        writeln!(w, "_{}.secret.push({});", any_id, id)?;
+        generate_tabs(w, tabs + 3)?;
+        writeln!(w, "break;")?;
+        generate_tabs(w, tabs + 2)?;
+        writeln!(w, "}}")?;
+
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "}}")?;
+
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "_{}", any_id)?;
+        generate_tabs(w, tabs)?;
+        write!(w, "}}")?;
+
+        Ok(())
+    }
+
+    fn generate_sum_in<W: Write>(
+        w: &mut W,
+        tabs: u16,
+        stack_len: usize,
+        for_in: &ForIn,
+        cx: &Context
+    ) -> Result<(), TranspileError> {
+        writeln!(w, "{{")?;
+
+        let id = cx.declare(stack_len, &for_in.name);
+        let sum_id = stack_len + 1;
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "let mut _{}: f64 = 0.0;", sum_id)?;
+
+        generate_tabs(w, tabs + 1)?;
+        write!(w, "for {} in iter(&", id)?;
+        generate_expression(w, tabs, stack_len, &for_in.iter, cx)?;
+        writeln!(w, ") {{")?;
+
+        generate_tabs(w, tabs + 2)?;
+        writeln!(w, "_{} += {{", sum_id)?;
+        generate_block(w, tabs + 3, stack_len + 1, &for_in.block, cx)?;
+        writeln!(w, "")?;
+        generate_tabs(w, tabs + 2)?;
+        writeln!(w, "}};")?;
+
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "}}")?;
+
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "_{}", sum_id)?;
+        generate_tabs(w, tabs)?;
+        write!(w, "}}")?;
+
+        Ok(())
+    }
+
+    fn generate_prod_in<W: Write>(
+        w: &mut W,
+        tabs: u16,
+        stack_len: usize,
+        for_in: &ForIn,
+        cx: &Context
+    ) -> Result<(), TranspileError> {
+        writeln!(w, "{{")?;
+
+        let id = cx.declare(stack_len, &for_in.name);
+        let prod_id = stack_len + 1;
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "let mut _{}: f64 = 1.0;", prod_id)?;
+
+        generate_tabs(w, tabs + 1)?;
+        write!(w, "for {} in iter(&", id)?;
+        generate_expression(w, tabs, stack_len, &for_in.iter, cx)?;
+        writeln!(w, ") {{")?;
+
+        generate_tabs(w, tabs + 2)?;
+        writeln!(w, "_{} *= {{", prod_id)?;
+        generate_block(w, tabs + 3, stack_len + 1, &for_in.block, cx)?;
+        writeln!(w, "")?;
+        generate_tabs(w, tabs + 2)?;
+        writeln!(w, "}};")?;
+
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "}}")?;
+
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "_{}", prod_id)?;
+        generate_tabs(w, tabs)?;
+        write!(w, "}}")?;
+
+        Ok(())
+    }
+
+    fn generate_max_in<W: Write>(
+        w: &mut W,
+        tabs: u16,
+        stack_len: usize,
+        for_in: &ForIn,
+        cx: &Context
+    ) -> Result<(), TranspileError> {
+        writeln!(w, "{{")?;
+
+        let id = cx.declare(stack_len, &for_in.name);
+        let max_id = stack_len + 1;
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "let mut _{}: Secret<f64, _> = Secret::new_f64(::std::f64::NAN);", max_id)?;
+
+        let track_id = stack_len + 2;
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "let mut _{} = None;", track_id)?;
+
+        generate_tabs(w, tabs + 1)?;
+        write!(w, "for {} in iter(&", id)?;
+        generate_expression(w, tabs, stack_len, &for_in.iter, cx)?;
+        writeln!(w, ") {{")?;
+
+        generate_tabs(w, tabs + 2)?;
+        let res_id = stack_len + 3;
+        writeln!(w, "let _{} = {{", res_id)?;
+        generate_block(w, tabs + 3, stack_len + 1, &for_in.block, cx)?;
+        writeln!(w, "")?;
+        generate_tabs(w, tabs + 2)?;
+        writeln!(w, "}};")?;
+        generate_tabs(w, tabs + 2)?;
+        writeln!(w, "if _{}.val.is_nan() || _{}.value() > _{}.value() {{", max_id, res_id, max_id)?;
+        generate_tabs(w, tabs + 3)?;
+        writeln!(w, "_{} = _{}.into();", max_id, res_id)?;
+        generate_tabs(w, tabs + 3)?;
+        writeln!(w, "_{} = Some({});", track_id, id)?;
+        generate_tabs(w, tabs + 2)?;
+        writeln!(w, "}}")?;
+
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "}}")?;
+
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "if let Some({}) = _{} {{", id, track_id)?;
+        generate_tabs(w, tabs + 2)?;
+        writeln!(w, "_{}.secret.push({});", max_id, id)?;
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "}}")?;
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "_{}", max_id)?;
+        generate_tabs(w, tabs)?;
+        write!(w, "}}")?;
+
+        Ok(())
+    }
+
+    fn generate_min_in<W: Write>(
+        w: &mut W,
+        tabs: u16,
+        stack_len: usize,
+        for_in: &ForIn,
+        cx: &Context
+    ) -> Result<(), TranspileError> {
+        writeln!(w, "{{")?;
+
+        let id = cx.declare(stack_len, &for_in.name);
+        let min_id = stack_len + 1;
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "let mut _{}: Secret<f64, _> = Secret::new_f64(::std::f64::NAN);", min_id)?;
+
+        let track_id = stack_len + 2;
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "let mut _{} = None;", track_id)?;
+
+        generate_tabs(w, tabs + 1)?;
+        write!(w, "for {} in iter(&", id)?;
+        generate_expression(w, tabs, stack_len, &for_in.iter, cx)?;
+        writeln!(w, ") {{")?;
+
+        generate_tabs(w, tabs + 2)?;
+        let res_id = stack_len + 3;
+        writeln!(w, "let _{} = {{", res_id)?;
+        generate_block(w, tabs + 3, stack_len + 1, &for_in.block, cx)?;
+        writeln!(w, "")?;
+        generate_tabs(w, tabs + 2)?;
+        writeln!(w, "}};")?;
+        generate_tabs(w, tabs + 2)?;
+        writeln!(w, "if _{}.val.is_nan() || _{}.value() < _{}.value() {{", min_id, res_id, min_id)?;
+        generate_tabs(w, tabs + 3)?;
+        writeln!(w, "_{} = _{}.into();", min_id, res_id)?;
+        generate_tabs(w, tabs + 3)?;
+        writeln!(w, "_{} = Some({});", track_id, id)?;
+        generate_tabs(w, tabs + 2)?;
+        writeln!(w, "}}")?;
+
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "}}")?;
+
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "if let Some({}) = _{} {{", id, track_id)?;
+        generate_tabs(w, tabs + 2)?;
+        writeln!(w, "_{}.secret.push({});", min_id, id)?;
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "}}")?;
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "_{}", min_id)?;
+        generate_tabs(w, tabs)?;
+        write!(w, "}}")?;
+
+        Ok(())
+    }
+
+    fn generate_sift_in<W: Write>(
+        w: &mut W,
+        tabs: u16,
+        stack_len: usize,
+        for_in: &ForIn,
+        cx: &Context
+    ) -> Result<(), TranspileError> {
+        writeln!(w, "{{")?;
+
+        let id = cx.declare(stack_len, &for_in.name);
+        let sift_id = stack_len + 1;
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "let mut _{} = vec![];", sift_id)?;
+
+        generate_tabs(w, tabs + 1)?;
+        write!(w, "for {} in iter(&", id)?;
+        generate_expression(w, tabs, stack_len, &for_in.iter, cx)?;
+        writeln!(w, ") {{")?;
+
+        generate_tabs(w, tabs + 2)?;
+        let res_id = stack_len + 3;
+        writeln!(w, "let _{} = {{", res_id)?;
+        generate_block(w, tabs + 3, stack_len + 1, &for_in.block, cx)?;
         writeln!(w, "")?;
         generate_tabs(w, tabs + 2)?;
         writeln!(w, "}};")?;
         generate_tabs(w, tabs + 2)?;
         writeln!(w, "_{}.push(_{});", sift_id, res_id)?;
 
-        generate_tabs(w, tabs + 2)?;
-        writeln!(w, "_{} += 1.0;", id)?;
-
         generate_tabs(w, tabs + 1)?;
         writeln!(w, "}}")?;
         generate_tabs(w, tabs + 1)?;
@@ -742,7 +1391,7 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         Ok(())
     }
 
-    fn generate_number<W: Write>(w: &mut W, number: &Number) -> io::Result<()> {
+    fn generate_number<W: Write>(w: &mut W, number: &Number) -> Result<(), TranspileError> {
         write!(w, "{}", number.num)?;
         if number.num % 1.0 == 0.0 {
             write!(w, ".0")?;
@@ -751,8 +1400,9 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         Ok(())
     }
 
-    fn generate_bool<W: Write>(w: &mut W, b: &Bool) -> io::Result<()> {
-        write!(w, "{}", b.val)
+    fn generate_bool<W: Write>(w: &mut W, b: &Bool) -> Result<(), TranspileError> {
+        write!(w, "{}", b.val)?;
+        Ok(())
     }
 
     fn generate_item<W: Write>(
@@ -760,12 +1410,18 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         tabs: u16,
         stack_len: usize,
         item: &Item,
-        module: &Module
-    ) -> io::Result<()> {
+        cx: &Context
+    ) -> Result<(), TranspileError> {
+        let mut object_struct = false;
         if let Some(ref stack_id) = item.static_stack_id.get() {
-            write!(w, "_{}", stack_len - stack_id)?;
+            write!(w, "{}", cx.name(stack_len - stack_id))?;
+            object_struct = cx.is_object_struct(stack_len - stack_id);
         }
         for (i, id) in item.ids.iter().enumerate() {
+            if let (0, true, &Id::String(_, ref key)) = (i, object_struct, id) {
+                write!(w, ".{}", key)?;
+                continue;
+            }
             write!(w, "[")?;
             match *id {
                 Id::String(_, ref text) => {
@@ -776,7 +1432,7 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
                 }
                 Id::Expression(ref expr) => {
                     write!(w, "index::ind(")?;
-                    generate_expression(w, tabs, stack_len + i, expr, module)?;
+                    generate_expression(w, tabs, stack_len + i, expr, cx)?;
                     write!(w, ")")?;
                 }
             }
@@ -791,8 +1447,8 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         tabs: u16,
         stack_len: usize,
         binop: &BinOpExpression,
-        module: &Module
-    ) -> io::Result<()> {
+        cx: &Context
+    ) -> Result<(), TranspileError> {
         use dyon::ast::BinOp as B;
 
         match binop.op {
@@ -806,25 +1462,25 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
             B::Pow => write!(w, "binop::pow")?,
             B::AndAlso => {
                 write!(w, "(")?;
-                generate_expression(w, tabs, stack_len, &binop.left, module)?;
+                generate_expression(w, tabs, stack_len, &binop.left, cx)?;
                 write!(w, " && ")?;
-                generate_expression(w, tabs, stack_len, &binop.right, module)?;
+                generate_expression(w, tabs, stack_len, &binop.right, cx)?;
                 write!(w, ")")?;
                 return Ok(())
             }
             B::OrElse => {
                 write!(w, "(")?;
-                generate_expression(w, tabs, stack_len, &binop.left, module)?;
+                generate_expression(w, tabs, stack_len, &binop.left, cx)?;
                 write!(w, " || ")?;
-                generate_expression(w, tabs, stack_len, &binop.right, module)?;
+                generate_expression(w, tabs, stack_len, &binop.right, cx)?;
                 write!(w, ")")?;
                 return Ok(())
             }
         }
         write!(w, "(&")?;
-        generate_expression(w, tabs, stack_len, &binop.left, module)?;
+        generate_expression(w, tabs, stack_len, &binop.left, cx)?;
         write!(w, ", &")?;
-        generate_expression(w, tabs, stack_len, &binop.right, module)?;
+        generate_expression(w, tabs, stack_len, &binop.right, cx)?;
         write!(w, ")")?;
 
         Ok(())
@@ -835,8 +1491,8 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         tabs: u16,
         stack_len: usize,
         compare: &Compare,
-        module: &Module
-    ) -> io::Result<()> {
+        cx: &Context
+    ) -> Result<(), TranspileError> {
         use dyon::ast::CompareOp as C;
 
         match compare.op {
@@ -848,120 +1504,39 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
             C::NotEqual => write!(w, "compop::not_equal")?,
         }
         write!(w, "(&")?;
-        generate_expression(w, tabs, stack_len, &compare.left, module)?;
+        generate_expression(w, tabs, stack_len, &compare.left, cx)?;
         write!(w, ", &")?;
-        generate_expression(w, tabs, stack_len, &compare.right, module)?;
+        generate_expression(w, tabs, stack_len, &compare.right, cx)?;
         write!(w, ")")?;
         Ok(())
     }
 
+    /// Generates code for an array.
+    ///
+    /// If the items have different types, then they are converted into Dyon variables.
     fn generate_array<W: Write>(
         w: &mut W,
         tabs: u16,
         stack_len: usize,
         array: &Array,
-        module: &Module
-    ) -> io::Result<()> {
-        // Used to infer types from array.
-        //
-        // If there are provably different types,
-        // then use a Dyon variable inside the array.
-        #[derive(PartialEq, Eq, Clone, Debug)]
-        enum ArrayType {
-            Bool,
-            F64,
-            Str,
-            Vec4,
-            Link,
-            Variable,
-            Object,
-            Array(Option<Box<ArrayType>>),
-        }
-
-        impl ArrayType {
-            fn infer(expr: &Expression) -> Option<Self> {
-                use dyon::ast::Expression as E;
-
-                match *expr {
-                    E::Number(_) |
-                    E::Sum(_) |
-                    E::Prod(_) => Some(ArrayType::F64),
-                    E::Text(_) => Some(ArrayType::Str),
-                    E::Bool(_) => Some(ArrayType::Bool),
-                    E::Vec4(_) => Some(ArrayType::Vec4),
-                    E::Link(_) => Some(ArrayType::Link),
-                    E::Object(_) => Some(ArrayType::Object),
-                    E::Array(ref array) => {
-                        let mut ty: Option<ArrayType> = None;
-                        let n = array.items.len();
-                        for i in 0..n {
-                            if let Some(ref mut ty) = ty {
-                                ty.refine(&array.items[i]);
-                            } else {
-                                ty = ArrayType::infer(&array.items[i]);
-                            }
-                        }
-                        Some(ArrayType::Array(ty.map(|ty| Box::new(ty))))
-                    }
-                    _ => None,
-                }
-            }
-
-            fn refine(&mut self, expr: &Expression) {
-                if let Some(array_type) = ArrayType::infer(expr) {
-                    // println!("TEST self {:?} array_type {:?}", self, array_type);
-                    *self = self.refine_match(&array_type);
-                    // println!("TEST new self {:?}", self);
-                }
-            }
-
-            fn refine_match(&self, other: &ArrayType) -> ArrayType {
-                match (&*self, other) {
-                    (&ArrayType::Array(None), &ArrayType::Array(Some(_))) => other.clone(),
-                    (&ArrayType::Array(Some(_)), &ArrayType::Array(None)) => self.clone(),
-                    (&ArrayType::Array(Some(ref a)), &ArrayType::Array(Some(ref b))) => {
-                        let res = a.refine_match(b);
-                        if res == ArrayType::Variable &&
-                           (**a != ArrayType::Variable || **b != ArrayType::Variable)
-                        {
-                            ArrayType::Variable
-                        } else {
-                            ArrayType::Array(Some(Box::new(res)))
-                        }
-                    }
-                    (x, y) if x == y => x.clone(),
-                    (_, _) => ArrayType::Variable,
-                }
-            }
-        }
+        ty: Option<&Type>,
+        cx: &Context
+    ) -> Result<(), TranspileError> {
+        let dynamic = match ty {
+            Some(&Type::Array(ref item_ty)) => if let Type::Any = **item_ty {true} else {false},
+            _ => false,
+        };
 
         write!(w, "vec![")?;
         let n = array.items.len();
-
-        let mut ty: Option<ArrayType> = None;
-        for i in 0..n {
-            if let Some(ref mut ty) = ty {
-                ty.refine(&array.items[i]);
+        for (i, it) in array.items.iter().enumerate() {
+            if dynamic && !cx.types.is_variable(it) {
+                generate_variable(w, tabs, stack_len, it, cx)?;
             } else {
-                ty = ArrayType::infer(&array.items[i]);
-            }
-        }
-
-        if let Some(ArrayType::Variable) = ty {
-            // println!("TEST array {:?}", array);
-            // println!("TEST ty {:?}", ty);
-            for (i, it) in array.items.iter().enumerate() {
-                generate_variable(w, tabs, stack_len, it, module)?;
-                if (i + 1) != n {
-                    write!(w, ", ")?;
-                }
+                generate_expression(w, tabs, stack_len, it, cx)?;
             }
-        } else {
-            for (i, it) in array.items.iter().enumerate() {
-                generate_expression(w, tabs, stack_len, it, module)?;
-                if (i + 1) != n {
-                    write!(w, ", ")?;
-                }
+            if (i + 1) != n {
+                write!(w, ", ")?;
             }
         }
         write!(w, "]")?;
@@ -974,8 +1549,8 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         tabs: u16,
         stack_len: usize,
         vec4: &Vec4,
-        module: &Module
-    ) -> io::Result<()> {
+        cx: &Context
+    ) -> Result<(), TranspileError> {
         let n = vec4.args.len();
 
         // Sum the number of contracted coordinates.
@@ -1002,7 +1577,7 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
                     generate_tabs(w, tabs + 1)?;
                     write!(w, "let ref _{} = ", id)?;
                     generate_expression(w, tabs + 2, stack_len + swizzles_recount,
-                                        &swizzle.expr, module)?;
+                                        &swizzle.expr, cx)?;
                     writeln!(w, ";")?;
                     swizzle_id.push(id);
 
@@ -1033,7 +1608,7 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
                     }
                     swizzle_ind += 1;
                 } else {
-                    generate_expression(w, tabs + 2, stack_len, exp, module)?;
+                    generate_expression(w, tabs + 2, stack_len, exp, cx)?;
                 }
                 // Skip zeroes that are removed because of swizzling.
                 if (i + 1 + swizzles) >= n {break;}
@@ -1047,7 +1622,7 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         } else {
             write!(w, "[")?;
             for (i, exp) in vec4.args.iter().enumerate() {
-                generate_expression(w, tabs, stack_len, exp, module)?;
+                generate_expression(w, tabs, stack_len, exp, cx)?;
                 // Skip zeroes that are removed because of swizzling.
                 if (i + 1) != n {
                     write!(w, ", ")?;
@@ -1064,59 +1639,79 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         tabs: u16,
         stack_len: usize,
         assign: &Assign,
-        module: &Module
-    ) -> io::Result<()> {
+        cx: &Context
+    ) -> Result<(), TranspileError> {
         use dyon::ast::AssignOp as A;
 
         if let Expression::Item(ref item) = assign.left {
             if item.ids.len() == 0 {
                 match assign.op {
+                    A::Assign if item.current => {
+                        let name = cx.declare_current(stack_len, &item.name, false);
+                        let ty = cx.currents.ty(&item.name);
+                        if ty == "Variable" {
+                            write!(w, "let mut {}: Variable = variable(&", name)?;
+                            generate_expression(w, tabs, stack_len, &assign.right, cx)?;
+                            write!(w, ")")?;
+                            return Ok(())
+                        }
+                        if declaration_type(&assign.right, cx).is_some() {
+                            write!(w, "let mut {}: {} = ", name, ty)?;
+                        } else {
+                            write!(w, "let mut {} = ", name)?;
+                        }
+                    }
                     A::Assign => {
-                        write!(w, "let mut _{} = ", stack_len)?;
+                        write!(w, "let mut {}", cx.declare(stack_len, &item.name))?;
+                        if let Some(ty) = declaration_type(&assign.right, cx) {
+                            write!(w, ": ")?;
+                            generate_type(w, ty, assign.right.source_range(), cx)?;
+                        }
+                        write!(w, " = ")?;
                     }
                     A::Set => {
                         write!(w, "assign(&mut ")?;
-                        generate_expression(w, tabs, stack_len, &assign.left, module)?;
+                        generate_expression(w, tabs, stack_len, &assign.left, cx)?;
                         write!(w, ", &")?;
-                        generate_expression(w, tabs, stack_len, &assign.right, module)?;
+                        generate_expression(w, tabs, stack_len, &assign.right, cx)?;
                         write!(w, ")")?;
                         return Ok(())
                     }
                     A::Add => {
-                        generate_expression(w, tabs, stack_len, &assign.left, module)?;
+                        generate_expression(w, tabs, stack_len, &assign.left, cx)?;
                         write!(w, " += ")?;
                     }
                     A::Sub => {
-                        generate_expression(w, tabs, stack_len, &assign.left, module)?;
+                        generate_expression(w, tabs, stack_len, &assign.left, cx)?;
                         write!(w, " -= ")?;
                     }
                     A::Mul => {
-                        generate_expression(w, tabs, stack_len, &assign.left, module)?;
+                        generate_expression(w, tabs, stack_len, &assign.left, cx)?;
                         write!(w, " *= ")?;
                     }
                     A::Div => {
-                        generate_expression(w, tabs, stack_len, &assign.left, module)?;
+                        generate_expression(w, tabs, stack_len, &assign.left, cx)?;
                         write!(w, " /= ")?;
                     }
                     A::Rem => {
-                        generate_expression(w, tabs, stack_len, &assign.left, module)?;
+                        generate_expression(w, tabs, stack_len, &assign.left, cx)?;
                         write!(w, " %= ")?;
                     }
                     A::Pow => {
-                        generate_expression(w, tabs, stack_len, &assign.left, module)?;
+                        generate_expression(w, tabs, stack_len, &assign.left, cx)?;
                         write!(w, " = binop::pow(&, ")?;
-                        generate_expression(w, tabs, stack_len, &assign.left, module)?;
+                        generate_expression(w, tabs, stack_len, &assign.left, cx)?;
                         write!(w, ", ")?;
-                        generate_expression(w, tabs, stack_len, &assign.right, module)?;
+                        generate_expression(w, tabs, stack_len, &assign.right, cx)?;
                         write!(w, ")")?;
                         return Ok(())
                     }
                 }
-                generate_expression(w, tabs, stack_len, &assign.right, module)?;
+                generate_expression(w, tabs, stack_len, &assign.right, cx)?;
             } else {
-                generate_expression(w, tabs, stack_len, &assign.left, module)?;
+                generate_expression(w, tabs, stack_len, &assign.left, cx)?;
                 write!(w, " = ")?;
-                generate_expression(w, tabs, stack_len, &assign.right, module)?;
+                generate_expression(w, tabs, stack_len, &assign.right, cx)?;
             }
         }
         Ok(())
@@ -1127,12 +1722,12 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         tabs: u16,
         stack_len: usize,
         if_expr: &If,
-        module: &Module
-    ) -> io::Result<()> {
+        cx: &Context
+    ) -> Result<(), TranspileError> {
         write!(w, "if cond(&")?;
-        generate_expression(w, tabs, stack_len, &if_expr.cond, module)?;
+        generate_expression(w, tabs, stack_len, &if_expr.cond, cx)?;
         writeln!(w, ") {{")?;
-        generate_block(w, tabs + 1, stack_len, &if_expr.true_block, module)?;
+        generate_block(w, tabs + 1, stack_len, &if_expr.true_block, cx)?;
         writeln!(w, "")?;
         generate_tabs(w, tabs)?;
         write!(w, "}}")?;
@@ -1140,16 +1735,16 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
             .zip(if_expr.else_if_blocks.iter())
         {
             write!(w, " else if cond(&")?;
-            generate_expression(w, tabs + 1, stack_len, else_if_cond, module)?;
+            generate_expression(w, tabs + 1, stack_len, else_if_cond, cx)?;
             writeln!(w, ") {{")?;
-            generate_block(w, tabs + 1, stack_len, &else_if_block, module)?;
+            generate_block(w, tabs + 1, stack_len, &else_if_block, cx)?;
             writeln!(w, "")?;
             generate_tabs(w, tabs)?;
             write!(w, "}}")?;
         }
         if let Some(ref else_block) = if_expr.else_block {
             writeln!(w, " else {{")?;
-            generate_block(w, tabs + 1, stack_len, &else_block, module)?;
+            generate_block(w, tabs + 1, stack_len, &else_block, cx)?;
             writeln!(w, "")?;
             generate_tabs(w, tabs)?;
             write!(w, "}}")?;
@@ -1162,8 +1757,8 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         tabs: u16,
         stack_len: usize,
         unop_expr: &UnOpExpression,
-        module: &Module
-    ) -> io::Result<()> {
+        cx: &Context
+    ) -> Result<(), TranspileError> {
         use dyon::ast::UnOp as U;
 
         match unop_expr.op {
@@ -1171,7 +1766,7 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
             U::Neg => write!(w, "unop::neg")?,
         }
         write!(w, "(&")?;
-        generate_expression(w, tabs, stack_len, &unop_expr.expr, module)?;
+        generate_expression(w, tabs, stack_len, &unop_expr.expr, cx)?;
         write!(w, ")")?;
         Ok(())
     }
@@ -1181,7 +1776,7 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         _tabs: u16,
         _stack_len: usize,
         br: &Break
-    ) -> io::Result<()> {
+    ) -> Result<(), TranspileError> {
         if let Some(ref label) = br.label {
             write!(w, "break '{}", label)?;
         } else {
@@ -1195,7 +1790,7 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         _tabs: u16,
         _stack_len: usize,
         c: &Continue
-    ) -> io::Result<()> {
+    ) -> Result<(), TranspileError> {
         if let Some(ref label) = c.label {
             write!(w, "continue '{}", label)?;
         } else {
@@ -1210,21 +1805,67 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         tabs: u16,
         stack_len: usize,
         expr: &Expression,
-        module: &Module
-    ) -> io::Result<()> {
+        cx: &Context
+    ) -> Result<(), TranspileError> {
         write!(w, "variable(&")?;
-        generate_expression(w, tabs, stack_len, expr, module)?;
+        generate_expression(w, tabs, stack_len, expr, cx)?;
         write!(w, ")")?;
         Ok(())
     }
 
+    /// Generates code that evaluates to a number, e.g. the range of a loop.
+    ///
+    /// Converts from a dynamic variable when the inferred type is `any`.
+    fn generate_f64<W: Write>(
+        w: &mut W,
+        tabs: u16,
+        stack_len: usize,
+        expr: &Expression,
+        cx: &Context
+    ) -> Result<(), TranspileError> {
+        if cx.types.is_variable(expr) {
+            write!(w, "from_variable::<f64>(&")?;
+            generate_expression(w, tabs, stack_len, expr, cx)?;
+            write!(w, ")")?;
+        } else {
+            generate_expression(w, tabs, stack_len, expr, cx)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the inferred type used to annotate a declaration.
+    ///
+    /// Items, blocks and `clone` might evaluate to references,
+    /// so their types are left to the Rust compiler.
+    fn declaration_type<'b>(exp: &Expression, cx: &'b Context) -> Option<&'b Type> {
+        use dyon::ast::Expression as E;
+
+        match *exp {
+            E::Number(_) | E::Bool(_) | E::Text(_) | E::Vec4(_) | E::Array(_) | E::Link(_) |
+            E::BinOp(_) | E::Compare(_) | E::UnOp(_) | E::Try(_) | E::Go(_) => {}
+            E::Call(ref call) if call_name(call) != "clone" => {}
+            _ => return None,
+        }
+        cx.types.get(exp).and_then(|ty| if has_rust_type(ty) {Some(ty)} else {None})
+    }
+
+    /// Returns `true` if the type is generated without type parameters or structs.
+    fn has_rust_type(ty: &Type) -> bool {
+        match *ty {
+            Type::F64 | Type::Bool | Type::Vec4 | Type::Text | Type::Link | Type::Any => true,
+            Type::Array(ref ty) | Type::Option(ref ty) |
+            Type::Result(ref ty) | Type::Thread(ref ty) => has_rust_type(ty),
+            _ => false,
+        }
+    }
+
     fn generate_object<W: Write>(
         w: &mut W,
         tabs: u16,
         stack_len: usize,
         obj: &Object,
-        module: &Module
-    ) -> io::Result<()> {
+        cx: &Context
+    ) -> Result<(), TranspileError> {
         writeln!(w, "{{")?;
         let id = stack_len;
         generate_tabs(w, tabs + 1)?;
@@ -1232,7 +1873,7 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         for &(ref key, ref value) in &obj.key_values {
             generate_tabs(w, tabs + 1)?;
             write!(w, "_{}.insert(Arc::new({:?}.into()), ", id, key)?;
-            generate_variable(w, tabs + 1, stack_len, value, module)?;
+            generate_variable(w, tabs + 1, stack_len, value, cx)?;
             writeln!(w, ");")?;
         }
         generate_tabs(w, tabs + 1)?;
@@ -1242,19 +1883,243 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         Ok(())
     }
 
+    /// Generates code for the `?` operator.
+    ///
+    /// In a function returning `res`, the operand is converted with `try_res`,
+    /// which turns `none()` into an error and adds the function to the error trace.
+    fn generate_try<W: Write>(
+        w: &mut W,
+        tabs: u16,
+        stack_len: usize,
+        expr: &Expression,
+        cx: &Context
+    ) -> Result<(), TranspileError> {
+        let f = &cx.module.functions[cx.function.get()];
+        if let Type::Result(_) = f.ret {
+            let mut pos = SourcePos::new(expr.source_range());
+            pos.locate(&f.source);
+            let trace = format!("In function `{}` ({})", f.name, pos);
+            write!(w, "try_res(&")?;
+            generate_expression(w, tabs, stack_len, expr, cx)?;
+            write!(w, ", {:?})?", trace)?;
+            return Ok(());
+        }
+        generate_expression(w, tabs, stack_len, expr, cx)?;
+        if let Expression::Item(_) = *expr {
+            // Variables are cloned since `?` moves the value.
+            write!(w, ".clone()")?;
+        }
+        write!(w, "?")?;
+        Ok(())
+    }
+
+    fn generate_link<W: Write>(
+        w: &mut W,
+        tabs: u16,
+        stack_len: usize,
+        link: &Link,
+        cx: &Context
+    ) -> Result<(), TranspileError> {
+        writeln!(w, "{{")?;
+        let id = stack_len;
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "let mut _{} = Link::new();", id)?;
+        for item in &link.items {
+            generate_tabs(w, tabs + 1)?;
+            write!(w, "_{}.push(&", id)?;
+            generate_variable(w, tabs + 1, stack_len, item, cx)?;
+            writeln!(w, ").unwrap();")?;
+        }
+        generate_tabs(w, tabs + 1)?;
+        writeln!(w, "_{}", id)?;
+        generate_tabs(w, tabs)?;
+        write!(w, "}}")?;
+        Ok(())
+    }
+
+    /// Returns the Rust type of an object field, if it is statically known.
+    fn object_field_type(exp: &Expression) -> Option<&'static str> {
+        use dyon::ast::Expression as E;
+
+        match *exp {
+            E::Number(_) |
+            E::Sum(_) |
+            E::SumIn(_) |
+            E::Prod(_) |
+            E::ProdIn(_) => Some("f64"),
+            E::Bool(_) => Some("bool"),
+            E::Vec4(_) => Some("[f32; 4]"),
+            E::Text(_) => Some("Arc<String>"),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the expressions only access fields of the object with literal keys.
+    ///
+    /// Printing the object is allowed, since the struct implements `WriteLn`.
+    /// Unknown expressions are assumed to use the object in other ways.
+    fn only_field_access(exps: &[Expression], name: &str, keys: &[Arc<String>]) -> bool {
+        use dyon::ast::Expression as E;
+        use std::mem;
+
+        let mut res = true;
+        // Set when the next expression visited is an object printed directly.
+        let mut print_arg = false;
+        for exp in exps {
+            visit::walk_expression(exp, &mut |exp| match *exp {
+                E::Item(ref item) => {
+                    if mem::replace(&mut print_arg, false) || &**item.name != name {return}
+                    match item.ids.first() {
+                        Some(&Id::String(_, ref key)) if keys.iter().any(|k| k == key) => {}
+                        _ => res = false,
+                    }
+                }
+                E::Call(ref call) => {
+                    print_arg = match (call_name(call), &call.args[..]) {
+                        ("print", &[E::Item(ref item)]) |
+                        ("println", &[E::Item(ref item)]) => item.ids.is_empty(),
+                        _ => false,
+                    };
+                }
+                E::Number(_) | E::Text(_) | E::Bool(_) | E::Break(_) | E::Continue(_) |
+                E::BinOp(_) | E::Compare(_) | E::Assign(_) | E::UnOp(_) | E::Return(_) |
+                E::Try(_) | E::Block(_) | E::Array(_) | E::Vec4(_) | E::Link(_) | E::Object(_) |
+                E::If(_) | E::For(_) | E::ForN(_) | E::Sum(_) | E::Prod(_) | E::Min(_) |
+                E::Max(_) | E::Sift(_) | E::Any(_) | E::All(_) | E::ForIn(_) | E::SumIn(_) |
+                E::ProdIn(_) | E::MinIn(_) | E::MaxIn(_) | E::SiftIn(_) | E::AnyIn(_) |
+                E::AllIn(_) => {}
+                _ => res = false,
+            });
+        }
+        res
+    }
+
+    /// Returns the struct fields if an object declaration can use a Rust struct.
+    fn object_struct_fields(
+        assign: &Assign,
+        rest: &[Expression],
+        cx: &Context
+    ) -> Option<Vec<(Arc<String>, &'static str)>> {
+        use context::is_keyword;
+
+        if !cx.settings.object_structs {return None}
+        if let AssignOp::Assign = assign.op {} else {return None}
+        let name = if let Expression::Item(ref item) = assign.left {
+            item.name.clone()
+        } else {
+            return None
+        };
+        let obj = if let Expression::Object(ref obj) = assign.right {obj} else {return None};
+
+        let mut fields = vec![];
+        for &(ref key, ref value) in &obj.key_values {
+            let valid = !key.is_empty() && !is_keyword(key) &&
+                !key.chars().next().unwrap().is_numeric() &&
+                key.chars().all(|c| c.is_alphanumeric() || c == '_');
+            if !valid {return None}
+            fields.push((key.clone(), object_field_type(value)?));
+        }
+        let keys: Vec<Arc<String>> = fields.iter().map(|f| f.0.clone()).collect();
+        if only_field_access(rest, &name, &keys) {
+            Some(fields)
+        } else {
+            None
+        }
+    }
+
+    /// Generates a declaration of an object as a Rust struct.
+    fn generate_object_struct<W: Write>(
+        w: &mut W,
+        tabs: u16,
+        stack_len: usize,
+        assign: &Assign,
+        fields: Vec<(Arc<String>, &'static str)>,
+        cx: &Context
+    ) -> Result<(), TranspileError> {
+        let item = if let Expression::Item(ref item) = assign.left {item} else {unreachable!()};
+        let obj = if let Expression::Object(ref obj) = assign.right {obj} else {unreachable!()};
+
+        let struct_name = cx.object_struct(fields);
+        write!(w, "let mut {} = {} {{", cx.declare(stack_len, &item.name), struct_name)?;
+        let n = obj.key_values.len();
+        for (i, &(ref key, ref value)) in obj.key_values.iter().enumerate() {
+            write!(w, "{}: ", key)?;
+            generate_expression(w, tabs, stack_len, value, cx)?;
+            if (i + 1) != n {
+                write!(w, ", ")?;
+            }
+        }
+        write!(w, "}}")?;
+        cx.set_object_struct(stack_len, struct_name);
+        Ok(())
+    }
+
+    /// Generates the structs used for objects with static keys.
+    fn generate_object_struct_defs<W: Write>(w: &mut W, cx: &Context) -> Result<(), TranspileError> {
+        let vis = if cx.settings.public {"pub "} else {""};
+        for s in cx.object_structs.borrow().iter() {
+            writeln!(w, "#[derive(Clone)]")?;
+            writeln!(w, "{}struct {} {{", vis, s.name)?;
+            for &(ref key, ty) in &s.fields {
+                generate_tabs(w, 1)?;
+                writeln!(w, "{}{}: {},", vis, key, ty)?;
+            }
+            writeln!(w, "}}")?;
+
+            writeln!(w, "impl WriteLn for {} {{", s.name)?;
+            generate_tabs(w, 1)?;
+            writeln!(w, "fn write<W: ::std::io::Write>(&self, w: &mut W) -> ::std::io::Result<()> {{")?;
+            for (i, &(ref key, _)) in s.fields.iter().enumerate() {
+                generate_tabs(w, 2)?;
+                if i == 0 {
+                    writeln!(w, "write!(w, \"{{{{{}: \")?;", key)?;
+                } else {
+                    writeln!(w, "write!(w, \", {}: \")?;", key)?;
+                }
+                generate_tabs(w, 2)?;
+                writeln!(w, "self.{}.write(w)?;", key)?;
+            }
+            generate_tabs(w, 2)?;
+            if s.fields.is_empty() {
+                writeln!(w, "write!(w, \"{{{{}}}}\")")?;
+            } else {
+                writeln!(w, "write!(w, \"}}}}\")")?;
+            }
+            generate_tabs(w, 1)?;
+            writeln!(w, "}}")?;
+            writeln!(w, "}}")?;
+
+            writeln!(w, "impl ToVariable for {} {{", s.name)?;
+            generate_tabs(w, 1)?;
+            writeln!(w, "fn to_variable(&self) -> Variable {{")?;
+            generate_tabs(w, 2)?;
+            writeln!(w, "let mut obj: HashMap<Arc<String>, Variable> = HashMap::new();")?;
+            for &(ref key, _) in &s.fields {
+                generate_tabs(w, 2)?;
+                writeln!(w, "obj.insert(Arc::new({:?}.into()), variable(&self.{}));", key, key)?;
+            }
+            generate_tabs(w, 2)?;
+            writeln!(w, "Variable::Object(Arc::new(obj))")?;
+            generate_tabs(w, 1)?;
+            writeln!(w, "}}")?;
+            writeln!(w, "}}")?;
+        }
+        Ok(())
+    }
+
     fn generate_call_closure<W: Write>(
         w: &mut W,
         tabs: u16,
         stack_len: usize,
         call_closure: &CallClosure,
-        module: &Module
-    ) -> io::Result<()> {
+        cx: &Context
+    ) -> Result<(), TranspileError> {
         write!(w, "(")?;
-        generate_item(w, tabs + 1, stack_len, &call_closure.item, module)?;
+        generate_item(w, tabs + 1, stack_len, &call_closure.item, cx)?;
         write!(w, ")(")?;
         let n = call_closure.args.len();
         for (i, arg) in call_closure.args.iter().enumerate() {
-            generate_expression(w, tabs + 1, stack_len, arg, module)?;
+            generate_expression(w, tabs + 1, stack_len, arg, cx)?;
             if (i + 1) < n {
                 write!(w, ", ")?;
             }
@@ -1268,19 +2133,19 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         tabs: u16,
         stack_len: usize,
         closure: &Closure,
-        module: &Module
-    ) -> io::Result<()> {
+        cx: &Context
+    ) -> Result<(), TranspileError> {
         write!(w, "|")?;
         let n = closure.args.len();
-        for (i, _) in closure.args.iter().enumerate() {
-            write!(w, "_{}", stack_len + i)?;
+        for (i, arg) in closure.args.iter().enumerate() {
+            write!(w, "{}", cx.declare(stack_len + i, &arg.name))?;
             if (i + 1) < n {
                 write!(w, ", ")?;
             }
         }
         writeln!(w, "| {{")?;
         generate_tabs(w, tabs + 1)?;
-        generate_expression(w, tabs + 1, stack_len + n, &closure.expr, module)?;
+        generate_expression(w, tabs + 1, stack_len + n, &closure.expr, cx)?;
         writeln!(w, "")?;
         generate_tabs(w, tabs)?;
         write!(w, "}}")?;
@@ -1292,49 +2157,80 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         tabs: u16,
         stack_len: usize,
         exp: &Expression,
-        module: &Module,
-    ) -> io::Result<()> {
+        cx: &Context,
+    ) -> Result<(), TranspileError> {
         use dyon::ast::Expression as E;
 
         match *exp {
-            E::Call(ref call) => generate_call(w, tabs, stack_len, call, module)?,
+            E::Call(ref call) => generate_call(w, tabs, stack_len, call, cx)?,
             E::Text(ref text) => generate_text(w, text)?,
-            E::For(ref for_expr) => generate_for(w, tabs, stack_len, for_expr, module)?,
-            E::ForN(ref for_n) => generate_for_n(w, tabs, stack_len, for_n, module)?,
-            E::Sum(ref sum) => generate_sum(w, tabs, stack_len, sum, module)?,
-            E::Prod(ref prod) => generate_prod(w, tabs, stack_len, prod, module)?,
+            E::For(ref for_expr) => generate_for(w, tabs, stack_len, for_expr, cx)?,
+            E::ForN(ref for_n) => generate_for_n(w, tabs, stack_len, for_n, cx)?,
+            E::Sum(ref sum) => generate_sum(w, tabs, stack_len, sum, cx)?,
+            E::Prod(ref prod) => generate_prod(w, tabs, stack_len, prod, cx)?,
             E::Number(ref number) => generate_number(w, number)?,
             E::Bool(ref b) => generate_bool(w, b)?,
-            E::Item(ref item) => generate_item(w, tabs, stack_len, item, module)?,
-            E::BinOp(ref binop) => generate_binop(w, tabs, stack_len, binop, module)?,
-            E::Vec4(ref vec4) => generate_vec4(w, tabs, stack_len, vec4, module)?,
-            E::Array(ref array) => generate_array(w, tabs, stack_len, array, module)?,
-            E::Assign(ref assign) => generate_assign(w, tabs, stack_len, assign, module)?,
-            E::All(ref for_n) => generate_all_n(w, tabs, stack_len, for_n, module)?,
-            E::Any(ref for_n) => generate_any_n(w, tabs, stack_len, for_n, module)?,
-            E::Compare(ref compare) => generate_compare(w, tabs, stack_len, compare, module)?,
-            E::If(ref if_expr) => generate_if(w, tabs, stack_len, if_expr, module)?,
-            E::UnOp(ref unop) => generate_unop(w, tabs, stack_len, unop, module)?,
-            E::Max(ref for_n) => generate_max_n(w, tabs, stack_len, for_n, module)?,
-            E::Min(ref for_n) => generate_min_n(w, tabs, stack_len, for_n, module)?,
-            E::Sift(ref for_n) => generate_sift_n(w, tabs, stack_len, for_n, module)?,
+            E::Item(ref item) => generate_item(w, tabs, stack_len, item, cx)?,
+            E::BinOp(ref binop) => generate_binop(w, tabs, stack_len, binop, cx)?,
+            E::Vec4(ref vec4) => generate_vec4(w, tabs, stack_len, vec4, cx)?,
+            E::Array(ref array) => generate_array(w, tabs, stack_len, array, cx.types.get(exp), cx)?,
+            E::Assign(ref assign) => generate_assign(w, tabs, stack_len, assign, cx)?,
+            E::All(ref for_n) => generate_all_n(w, tabs, stack_len, for_n, cx)?,
+            E::Any(ref for_n) => generate_any_n(w, tabs, stack_len, for_n, cx)?,
+            E::Compare(ref compare) => generate_compare(w, tabs, stack_len, compare, cx)?,
+            E::If(ref if_expr) => generate_if(w, tabs, stack_len, if_expr, cx)?,
+            E::UnOp(ref unop) => generate_unop(w, tabs, stack_len, unop, cx)?,
+            E::Max(ref for_n) => generate_max_n(w, tabs, stack_len, for_n, cx)?,
+            E::Min(ref for_n) => generate_min_n(w, tabs, stack_len, for_n, cx)?,
+            E::Sift(ref for_n) => generate_sift_n(w, tabs, stack_len, for_n, cx)?,
+            E::ForIn(ref for_in) => generate_for_in(w, tabs, stack_len, for_in, cx)?,
+            E::SumIn(ref for_in) => generate_sum_in(w, tabs, stack_len, for_in, cx)?,
+            E::ProdIn(ref for_in) => generate_prod_in(w, tabs, stack_len, for_in, cx)?,
+            E::AllIn(ref for_in) => generate_all_in(w, tabs, stack_len, for_in, cx)?,
+            E::AnyIn(ref for_in) => generate_any_in(w, tabs, stack_len, for_in, cx)?,
+            E::MaxIn(ref for_in) => generate_max_in(w, tabs, stack_len, for_in, cx)?,
+            E::MinIn(ref for_in) => generate_min_in(w, tabs, stack_len, for_in, cx)?,
+            E::SiftIn(ref for_in) => generate_sift_in(w, tabs, stack_len, for_in, cx)?,
             E::Block(ref block) => {
                 writeln!(w, "{{")?;
-                generate_block(w, tabs + 1, stack_len, block, module)?;
+                generate_block(w, tabs + 1, stack_len, block, cx)?;
                 generate_tabs(w, tabs)?;
                 write!(w, "}}")?;
             }
             E::Break(ref br) => generate_break(w, tabs, stack_len, br)?,
             E::Continue(ref c) => generate_continue(w, tabs, stack_len, c)?,
-            E::Object(ref obj) => generate_object(w, tabs, stack_len, obj, module)?,
+            E::Object(ref obj) => generate_object(w, tabs, stack_len, obj, cx)?,
+            E::Link(ref link) => generate_link(w, tabs, stack_len, link, cx)?,
             E::Return(ref expr) => {
-                write!(w, "return ")?;
-                generate_expression(w, tabs + 1, stack_len, expr, module)?;
+                // Convert between dynamic variables and the return type.
+                let ret = &cx.module.functions[cx.function.get()].ret;
+                let convert = if cx.types.is_variable(expr) {
+                    if convert_from_variable(ret) {Some("from_variable")} else {None}
+                } else if let Type::Any = *ret {
+                    Some("variable")
+                } else {
+                    None
+                };
+                if let Some(convert) = convert {
+                    write!(w, "return {}(&", convert)?;
+                    generate_expression(w, tabs + 1, stack_len, expr, cx)?;
+                    write!(w, ")")?;
+                } else {
+                    write!(w, "return ")?;
+                    generate_expression(w, tabs + 1, stack_len, expr, cx)?;
+                }
             }
-            E::Closure(ref closure) => generate_closure(w, tabs + 1, stack_len, closure, module)?,
+            E::Try(ref expr) => generate_try(w, tabs, stack_len, expr, cx)?,
+            E::Go(ref go) => generate_go(w, tabs, stack_len, go, cx)?,
+            E::Closure(ref closure) => generate_closure(w, tabs + 1, stack_len, closure, cx)?,
             E::CallClosure(ref call_closure) => generate_call_closure(w, tabs + 1, stack_len,
-                                                                      call_closure, module)?,
-            ref x => unimplemented!("{:?}", x),
+                                                                      call_closure, cx)?,
+            ref x => {
+                cx.report(TranspileError::UnsupportedExpression {
+                    kind: expression_kind(x).into(),
+                    pos: SourcePos::new(x.source_range()),
+                })?;
+            }
         }
 
         Ok(())
@@ -1345,13 +2241,23 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         tabs: u16,
         stack_len: usize,
         block: &Block,
-        module: &Module,
-    ) -> io::Result<()> {
+        cx: &Context,
+    ) -> Result<(), TranspileError> {
         let n = block.expressions.len();
         let mut offset = 0;
         for (i, exp) in block.expressions.iter().enumerate() {
             generate_tabs(w, tabs)?;
-            generate_expression(w, tabs, stack_len + offset, exp, module)?;
+            let fields = if let &Expression::Assign(ref assign) = exp {
+                object_struct_fields(assign, &block.expressions[i + 1..], cx)
+                    .map(|fields| (assign, fields))
+            } else {
+                None
+            };
+            if let Some((assign, fields)) = fields {
+                generate_object_struct(w, tabs, stack_len + offset, assign, fields, cx)?;
+            } else {
+                generate_expression(w, tabs, stack_len + offset, exp, cx)?;
+            }
             if (i + 1) != n {
                 writeln!(w, ";")?;
             }
@@ -1367,33 +2273,102 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
         Ok(())
     }
 
+    /// Returns the Dyon name of the expression kind, e.g. `go` for `go` expressions.
+    fn expression_kind(exp: &Expression) -> &'static str {
+        use dyon::ast::Expression as E;
+
+        match *exp {
+            E::Link(_) => "link",
+            E::Object(_) => "object",
+            E::Array(_) => "array",
+            E::ArrayFill(_) => "array fill",
+            E::Return(_) | E::ReturnVoid(_) => "return",
+            E::Break(_) => "break",
+            E::Continue(_) => "continue",
+            E::Block(_) => "block",
+            E::Go(_) => "go",
+            E::Call(_) => "call",
+            E::Item(_) => "item",
+            E::BinOp(_) => "binary operator",
+            E::Assign(_) => "assignment",
+            E::Text(_) => "text",
+            E::Number(_) => "number",
+            E::Vec4(_) => "vec4",
+            E::Bool(_) => "bool",
+            E::For(_) | E::ForN(_) => "for",
+            E::ForIn(_) => "for in",
+            E::Sum(_) => "sum",
+            E::SumIn(_) => "sum in",
+            E::Prod(_) => "prod",
+            E::ProdIn(_) => "prod in",
+            E::Min(_) => "min",
+            E::MinIn(_) => "min in",
+            E::Max(_) => "max",
+            E::MaxIn(_) => "max in",
+            E::Sift(_) => "sift",
+            E::SiftIn(_) => "sift in",
+            E::Any(_) => "any",
+            E::AnyIn(_) => "any in",
+            E::All(_) => "all",
+            E::AllIn(_) => "all in",
+            E::If(_) => "if",
+            E::Compare(_) => "comparison",
+            E::UnOp(_) => "unary operator",
+            E::Variable(_, _) => "constant",
+            E::Try(_) => "?",
+            E::Swizzle(_) => "swizzle",
+            E::Closure(_) => "closure",
+            E::CallClosure(_) => "closure call",
+            E::Grab(_) => "grab",
+            E::Loop(_) => "loop",
+            _ => "unknown",
+        }
+    }
+
     fn generate_type<W: Write>(
         w: &mut W,
-        ty: &Type
-    ) -> io::Result<()> {
+        ty: &Type,
+        range: Range,
+        cx: &Context
+    ) -> Result<(), TranspileError> {
         match *ty {
             Type::F64 => write!(w, "f64")?,
             Type::Bool => write!(w, "bool")?,
             Type::Vec4 => write!(w, "[f32; 4]")?,
-            Type::Text => write!(w, "str")?,
+            Type::Text => write!(w, "Arc<String>")?,
+            Type::Link => write!(w, "Link")?,
+            Type::Any => write!(w, "Variable")?,
+            Type::Object => write!(w, "Arc<Object>")?,
             Type::Array(ref ty) => {
                 write!(w, "Vec<")?;
-                if let Type::Text = **ty {
-                    write!(w, "&")?;
-                }
-                generate_type(w, ty)?;
+                generate_type(w, ty, range, cx)?;
                 write!(w, ">")?;
             }
             Type::Secret(ref inner_ty) => {
                 write!(w, "Secret<")?;
-                generate_type(w, inner_ty)?;
+                generate_type(w, inner_ty, range, cx)?;
                 write!(w, ", f64>")?;
             }
+            Type::Result(ref inner_ty) => {
+                write!(w, "Result<")?;
+                generate_type(w, inner_ty, range, cx)?;
+                write!(w, ", Error>")?;
+            }
+            Type::Option(ref inner_ty) => {
+                write!(w, "Option<")?;
+                generate_type(w, inner_ty, range, cx)?;
+                write!(w, ">")?;
+            }
+            Type::Thread(ref inner_ty) => {
+                write!(w, "Thread<")?;
+                generate_type(w, inner_ty, range, cx)?;
+                write!(w, ">")?;
+            }
             Type::Closure(ref dfn) => {
                 write!(w, "Fn(")?;
                 let n = dfn.tys.len();
                 for (i, ty) in dfn.tys.iter().enumerate() {
-                    generate_type(w, ty)?;
+                    generate_type(w, ty, range, cx)?;
                     if (i + 1) < n {
                         write!(w, ", ")?;
                     }
@@ -1402,295 +2377,435 @@ pub fn generate_code<W: Write>(w: &mut W, module: &Module) -> io::Result<()> {
                 if let Type::Void = dfn.ret {}
                 else {
                     write!(w, " -> ")?;
-                    generate_type(w, &dfn.ret)?;
+                    generate_type(w, &dfn.ret, range, cx)?;
                 }
             }
-            _ => {}
+            ref x => {
+                cx.report(TranspileError::UnsupportedType {
+                    ty: x.description(),
+                    pos: SourcePos::new(range),
+                })?;
+            }
         }
         Ok(())
     }
 
-    writeln!(w, "#![allow(unused_imports)]")?;
-    writeln!(w, "#![allow(unreachable_code)]")?;
-    writeln!(w, "")?;
-    writeln!(w, "extern crate dyon;")?;
-    writeln!(w, "extern crate dyon_to_rust;")?;
-    writeln!(w, "")?;
-    writeln!(w, "use std::sync::Arc;")?;
-    writeln!(w, "use std::collections::HashMap;")?;
-    writeln!(w, "")?;
-    writeln!(w, "use dyon::{{Variable, Object}};")?;
-    writeln!(w, "use dyon_to_rust::intrinsics::*;")?;
-    writeln!(w, "use dyon_to_rust::*;")?;
-    writeln!(w, "")?;
-
-    for f in &module.functions {
-        if let Some(ind) = f.name.find('(') {
-            write!(w, "fn {}(", &f.name[..ind])?;
-        } else {
-            write!(w, "fn {}(", f.name)?;
+    fn generate_function<W: Write>(
+        w: &mut W,
+        tabs: u16,
+        vis: &str,
+        i: usize,
+        f: &Function,
+        cx: &Context
+    ) -> Result<(), TranspileError> {
+        cx.function.set(i);
+        // Dyon has already type checked the module, so leave the rest to the Rust compiler
+        // unless checking for problems.
+        if cx.is_checking() {
+            for conflict in cx.types.conflicts(i) {
+                cx.report(TranspileError::TypeConflict {
+                    expected: conflict.expected.description(),
+                    found: conflict.found.description(),
+                    pos: SourcePos::new(conflict.range),
+                })?;
+            }
         }
+        generate_tabs(w, tabs)?;
+        write!(w, "{}fn {}(", vis, cx.rust_names[i])?;
         let mut offset = 0;
         let n = f.args.len();
         for (i, arg) in f.args.iter().enumerate() {
+            let name = cx.declare(offset, &arg.name);
             if arg.mutable {
-                write!(w, "mut _{}: &mut ", offset)?;
+                write!(w, "mut {}: &mut ", name)?;
             } else {
-                write!(w, "_{}: &", offset)?;
+                write!(w, "{}: &", name)?;
             }
-            generate_type(w, &arg.ty)?;
+            generate_type(w, &arg.ty, arg.source_range, cx)?;
             if (i + 1) != n {
                 write!(w, ", ")?;
             }
             offset += 1;
         }
+        // Current objects declared by the function come after the arguments on the stack.
+        // Current objects only used by called functions are passed through.
+        for (j, name) in cx.currents.needs[i].iter().enumerate() {
+            if n > 0 || j > 0 {
+                write!(w, ", ")?;
+            }
+            let ty = cx.currents.ty(name);
+            let reference = if cx.currents.is_mutable(i, name) {"&mut "} else {"&"};
+            match f.currents.get(j) {
+                Some(current) => {
+                    let rust_name = cx.declare_current(offset, name, true);
+                    if current.mutable {
+                        write!(w, "mut {}: {}{}", rust_name, reference, ty)?;
+                    } else {
+                        write!(w, "{}: {}{}", rust_name, reference, ty)?;
+                    }
+                    offset += 1;
+                }
+                None => write!(w, "current_{}: {}{}", name, reference, ty)?,
+            }
+        }
         if let Type::Void = f.ret {
             writeln!(w, ") {{")?;
         } else {
             write!(w, ") -> ")?;
-            generate_type(w, &f.ret)?;
+            generate_type(w, &f.ret, f.source_range, cx)?;
             writeln!(w, " {{")?;
         }
-        generate_block(w, 1, offset, &f.block, module)?;
+        generate_block(w, tabs + 1, offset, &f.block, cx)?;
         if let Type::Void = f.ret {
             writeln!(w, ";")?;
-            writeln!(w, "}}")?;
         } else {
             writeln!(w, "")?;
+        }
+        generate_tabs(w, tabs)?;
+        writeln!(w, "}}")?;
+        Ok(())
+    }
+
+    if cx.settings.preamble {
+        writeln!(w, "#![allow(unused_imports)]")?;
+        writeln!(w, "#![allow(unreachable_code)]")?;
+        writeln!(w, "")?;
+        writeln!(w, "extern crate dyon;")?;
+        writeln!(w, "extern crate {};", cx.settings.runtime)?;
+        writeln!(w, "")?;
+    }
+    writeln!(w, "use std::sync::Arc;")?;
+    writeln!(w, "use std::collections::HashMap;")?;
+    writeln!(w, "")?;
+    writeln!(w, "use dyon::{{Variable, Object, Error}};")?;
+    writeln!(w, "use {}::intrinsics::*;", cx.settings.runtime)?;
+    writeln!(w, "use {}::*;", cx.settings.runtime)?;
+    writeln!(w, "")?;
+
+    let vis = if cx.settings.public {"pub "} else {""};
+    // Group functions by Rust module, in the order the modules appear.
+    let mut rust_modules: Vec<&Option<String>> = vec![];
+    for m in &cx.rust_modules {
+        if !rust_modules.contains(&m) {
+            rust_modules.push(m);
+        }
+    }
+    for m in rust_modules {
+        let tabs = if let Some(ref name) = *m {
+            writeln!(w, "{}mod {} {{", vis, name)?;
+            generate_tabs(w, 1)?;
+            writeln!(w, "use super::*;")?;
+            writeln!(w, "")?;
+            1
+        } else {
+            0
+        };
+        for (i, f) in cx.module.functions.iter().enumerate() {
+            if &cx.rust_modules[i] != m {continue}
+            // Functions in modules must be public to be called from other modules.
+            let vis = if tabs > 0 {"pub "} else {vis};
+            generate_function(w, tabs, vis, i, f, cx)?;
+        }
+        if m.is_some() {
             writeln!(w, "}}")?;
         }
     }
+    generate_object_struct_defs(w, cx)?;
 
     Ok(())
 }
 
 /// Generates code as a string from a Dyon module.
-pub fn generate_code_string(module: &Module) -> String {
+pub fn generate_code_string(module: &Module) -> Result<String, TranspileError> {
     let mut buf: Vec<u8> = vec![];
-    generate_code(&mut buf, &module).unwrap();
+    generate_code(&mut buf, &module)?;
     let txt = String::from_utf8(buf).unwrap();
-    txt
+    Ok(txt)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use dyon::{load, Module};
+    use std::fs::{self, File};
+    use std::io::Write;
+    use std::path::{Path, PathBuf};
 
-    #[test]
-    fn hello_world() {
-        let mut module = Module::new();
-        load("source/hello_world.dyon", &mut module).unwrap();
-        let code = generate_code_string(&module);
-        println!("{}", code);
-        assert_eq!(code, include_str!("../source/hello_world.rs"));
+    /// Returns `true` if expected files should be overwritten.
+    fn bless() -> bool {
+        ::std::env::var("DYON_TO_RUST_BLESS").map(|val| val == "1").unwrap_or(false)
     }
 
-    #[test]
-    fn count_to_ten() {
-        let mut module = Module::new();
-        load("source/count_to_ten.dyon", &mut module).unwrap();
-        let code = generate_code_string(&module);
-        println!("{}", code);
-        assert_eq!(code, include_str!("../source/count_to_ten.rs"));
-    }
+    /// Returns a unified diff of the lines that changed.
+    fn unified_diff(expected: &str, actual: &str) -> String {
+        use std::cmp::{max, min};
 
-    #[test]
-    fn count_double() {
-        let mut module = Module::new();
-        load("source/count_double.dyon", &mut module).unwrap();
-        let code = generate_code_string(&module);
-        println!("{}", code);
-        assert_eq!(code, include_str!("../source/count_double.rs"));
-    }
+        const CONTEXT: usize = 3;
 
-    #[test]
-    fn binops() {
-        let mut module = Module::new();
-        load("source/binops.dyon", &mut module).unwrap();
-        let code = generate_code_string(&module);
-        println!("{}", code);
-        assert_eq!(code, include_str!("../source/binops.rs"));
-    }
+        let a: Vec<&str> = expected.lines().collect();
+        let b: Vec<&str> = actual.lines().collect();
 
-    #[test]
-    fn vector4d() {
-        let mut module = Module::new();
-        load("source/vector4d.dyon", &mut module).unwrap();
-        let code = generate_code_string(&module);
-        println!("{}", code);
-        assert_eq!(code, include_str!("../source/vector4d.rs"));
-    }
+        // Length of the longest common subsequence of the remaining lines.
+        let mut lcs = vec![vec![0; b.len() + 1]; a.len() + 1];
+        for i in (0..a.len()).rev() {
+            for j in (0..b.len()).rev() {
+                lcs[i][j] = if a[i] == b[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    max(lcs[i + 1][j], lcs[i][j + 1])
+                };
+            }
+        }
 
-    #[test]
-    fn sum() {
-        let mut module = Module::new();
-        load("source/sum.dyon", &mut module).unwrap();
-        let code = generate_code_string(&module);
-        println!("{}", code);
-        assert_eq!(code, include_str!("../source/sum.rs"));
-    }
+        // Edit operations with line indices in the expected and actual text.
+        let mut ops: Vec<(char, &str, usize, usize)> = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < a.len() || j < b.len() {
+            if i < a.len() && j < b.len() && a[i] == b[j] {
+                ops.push((' ', a[i], i, j));
+                i += 1;
+                j += 1;
+            } else if j < b.len() && (i == a.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+                ops.push(('+', b[j], i, j));
+                j += 1;
+            } else {
+                ops.push(('-', a[i], i, j));
+                i += 1;
+            }
+        }
 
-    #[test]
-    fn prod() {
-        let mut module = Module::new();
-        load("source/prod.dyon", &mut module).unwrap();
-        let code = generate_code_string(&module);
-        println!("{}", code);
-        assert_eq!(code, include_str!("../source/prod.rs"));
+        let changes: Vec<usize> = (0..ops.len()).filter(|&k| ops[k].0 != ' ').collect();
+        let mut res = String::from("--- expected\n+++ actual\n");
+        let mut k = 0;
+        while k < changes.len() {
+            let start = changes[k].saturating_sub(CONTEXT);
+            // Join changes when their context overlaps.
+            while k + 1 < changes.len() && changes[k + 1] <= changes[k] + 2 * CONTEXT {
+                k += 1;
+            }
+            let end = min(changes[k] + 1 + CONTEXT, ops.len());
+            let hunk = &ops[start..end];
+            let old_len = hunk.iter().filter(|op| op.0 != '+').count();
+            let new_len = hunk.iter().filter(|op| op.0 != '-').count();
+            res.push_str(&format!("@@ -{},{} +{},{} @@\n",
+                hunk[0].2 + 1, old_len, hunk[0].3 + 1, new_len));
+            for &(tag, line, _, _) in hunk {
+                res.push(tag);
+                res.push_str(line);
+                res.push('\n');
+            }
+            k += 1;
+        }
+        if changes.is_empty() {
+            res.push_str("(only line endings differ)\n");
+        }
+        res
     }
 
-    #[test]
-    fn array() {
-        let mut module = Module::new();
-        load("source/array.dyon", &mut module).unwrap();
-        let code = generate_code_string(&module);
-        println!("{}", code);
-        assert_eq!(code, include_str!("../source/array.rs"));
+    /// Transpiles a Dyon file and compares with the expected Rust file.
+    ///
+    /// Returns an error message on mismatch, or overwrites the expected file when blessing.
+    fn check_golden(dyon_file: &Path, rust_file: &Path, settings: &CodeSettings) -> Result<(), String> {
+        check_golden_with_module(Module::new(), dyon_file, rust_file, settings)
     }
 
-    #[test]
-    fn array2() {
-        let mut module = Module::new();
-        load("source/array2.dyon", &mut module).unwrap();
-        let code = generate_code_string(&module);
-        println!("{}", code);
-        assert_eq!(code, include_str!("../source/array2.rs"));
+    /// Like `check_golden`, but loads the Dyon file into a module with external functions.
+    fn check_golden_with_module(
+        mut module: Module,
+        dyon_file: &Path,
+        rust_file: &Path,
+        settings: &CodeSettings
+    ) -> Result<(), String> {
+        load(&dyon_file.to_string_lossy(), &mut module)
+            .map_err(|err| format!("{}: Could not load:\n{}", dyon_file.display(), err))?;
+        let mut buf: Vec<u8> = vec![];
+        generate_code_with_settings(&mut buf, &module, settings)
+            .map_err(|err| format!("{}: {}", dyon_file.display(), err))?;
+        let code = String::from_utf8(buf).unwrap();
+
+        if bless() {
+            let mut file = File::create(rust_file).unwrap();
+            write!(file, "{}", code).unwrap();
+            return Ok(())
+        }
+        let expected = fs::read_to_string(rust_file).unwrap_or_default();
+        if code == expected {
+            Ok(())
+        } else {
+            Err(format!("{}: Generated code differs\n{}",
+                rust_file.display(), unified_diff(&expected, &code)))
+        }
     }
 
-    #[test]
-    fn array3() {
-        let mut module = Module::new();
-        load("source/array3.dyon", &mut module).unwrap();
-        let code = generate_code_string(&module);
-        println!("{}", code);
-        assert_eq!(code, include_str!("../source/array3.rs"));
+    /// Checks all pairs of ".dyon" and ".rs" files in a folder.
+    fn check_dir(dir: &str, settings: &CodeSettings) {
+        let mut files: Vec<PathBuf> = fs::read_dir(dir).unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().map(|ext| ext == "dyon").unwrap_or(false))
+            .collect();
+        files.sort();
+        assert!(!files.is_empty(), "No Dyon files found in `{}`", dir);
+
+        let failures: Vec<String> = files.iter()
+            .filter_map(|file| check_golden(file, &file.with_extension("rs"), settings).err())
+            .collect();
+        if !failures.is_empty() {
+            panic!("{} of {} files failed (set `DYON_TO_RUST_BLESS=1` to update):\n\n{}",
+                failures.len(), files.len(), failures.join("\n"));
+        }
     }
 
     #[test]
-    fn assign() {
-        let mut module = Module::new();
-        load("source/assign.dyon", &mut module).unwrap();
-        let code = generate_code_string(&module);
-        println!("{}", code);
-        assert_eq!(code, include_str!("../source/assign.rs"));
+    fn golden() {
+        check_dir("source", &CodeSettings::new());
     }
 
     #[test]
-    fn index() {
-        let mut module = Module::new();
-        load("source/index.dyon", &mut module).unwrap();
-        let code = generate_code_string(&module);
-        println!("{}", code);
-        assert_eq!(code, include_str!("../source/index.rs"));
+    fn variable_names() {
+        let mut settings = CodeSettings::new();
+        settings.variable_names = true;
+        check_dir("source/names", &settings);
     }
 
     #[test]
-    fn triple_index() {
-        let mut module = Module::new();
-        load("source/triple_index.dyon", &mut module).unwrap();
-        let code = generate_code_string(&module);
-        println!("{}", code);
-        assert_eq!(code, include_str!("../source/triple_index.rs"));
+    fn object_structs() {
+        let mut settings = CodeSettings::new();
+        settings.object_structs = true;
+        check_dir("source/structs", &settings);
     }
 
     #[test]
-    fn call() {
-        let mut module = Module::new();
-        load("source/call.dyon", &mut module).unwrap();
-        let code = generate_code_string(&module);
-        println!("{}", code);
-        assert_eq!(code, include_str!("../source/call.rs"));
+    fn no_preamble() {
+        let mut settings = CodeSettings::new();
+        settings.preamble = false;
+        check_golden(Path::new("source/hello_world.dyon"),
+            Path::new("source/module/hello_world.rs"), &settings).unwrap();
     }
 
     #[test]
-    fn compare() {
-        let mut module = Module::new();
-        load("source/compare.dyon", &mut module).unwrap();
-        let code = generate_code_string(&module);
-        println!("{}", code);
-        assert_eq!(code, include_str!("../source/compare.rs"));
+    fn library() {
+        let mut settings = CodeSettings::library();
+        settings.runtime = String::from("::runtime");
+        check_golden(Path::new("source/module/library.dyon"),
+            Path::new("source/module/library.rs"), &settings).unwrap();
     }
 
     #[test]
-    fn if_expr() {
+    fn imported_module() {
         let mut module = Module::new();
-        load("source/if.dyon", &mut module).unwrap();
-        let code = generate_code_string(&module);
-        println!("{}", code);
-        assert_eq!(code, include_str!("../source/if.rs"));
+        load("source/modules/lib.dyon", &mut module).unwrap();
+        check_golden_with_module(module, Path::new("source/modules/main.dyon"),
+            Path::new("source/modules/main.rs"), &CodeSettings::new()).unwrap();
     }
 
     #[test]
-    fn unop() {
+    fn imported_modules() {
         let mut module = Module::new();
-        load("source/unop.dyon", &mut module).unwrap();
-        let code = generate_code_string(&module);
-        println!("{}", code);
-        assert_eq!(code, include_str!("../source/unop.rs"));
+        load("source/modules/lib.dyon", &mut module).unwrap();
+        load("source/modules/shapes.dyon", &mut module).unwrap();
+        check_golden_with_module(module, Path::new("source/modules/shapes_main.dyon"),
+            Path::new("source/modules/shapes_main.rs"), &CodeSettings::new()).unwrap();
     }
 
     #[test]
-    fn secret() {
+    fn external() {
+        use dyon::{Dfn, Lt, Runtime};
+        use dyon::ty::Type;
+        use std::sync::Arc;
+
+        fn draw(_: &mut Runtime) -> Result<(), String> {Ok(())}
+        fn distance(_: &mut Runtime) -> Result<(), String> {Ok(())}
+
         let mut module = Module::new();
-        load("source/secret.dyon", &mut module).unwrap();
-        let code = generate_code_string(&module);
-        println!("{}", code);
-        assert_eq!(code, include_str!("../source/secret.rs"));
+        module.add(Arc::new("draw".into()), draw, Dfn {
+            lts: vec![Lt::Default],
+            tys: vec![Type::Vec4],
+            ret: Type::Void,
+        });
+        module.add(Arc::new("distance".into()), distance, Dfn {
+            lts: vec![Lt::Default, Lt::Default],
+            tys: vec![Type::Vec4, Type::Vec4],
+            ret: Type::F64,
+        });
+        let mut settings = CodeSettings::new();
+        settings.externals.add("draw", "engine::draw", ArgConvention::Value);
+        settings.externals.add("distance", "engine::math::distance", ArgConvention::Ref);
+        check_golden_with_module(module, Path::new("source/external/draw.dyon"),
+            Path::new("source/external/draw.rs"), &settings).unwrap();
     }
 
     #[test]
-    fn loop_() {
+    fn unregistered_external() {
+        use dyon::{Dfn, Runtime};
+        use dyon::ty::Type;
+        use std::sync::Arc;
+
+        fn draw(_: &mut Runtime) -> Result<(), String> {Ok(())}
+
         let mut module = Module::new();
-        load("source/loop.dyon", &mut module).unwrap();
-        let code = generate_code_string(&module);
-        println!("{}", code);
-        assert_eq!(code, include_str!("../source/loop.rs"));
+        module.add(Arc::new("draw".into()), draw, Dfn {
+            lts: vec![],
+            tys: vec![],
+            ret: Type::Void,
+        });
+        load("source/external/unregistered.dyon", &mut module).unwrap();
+        match generate_code_string(&module) {
+            Err(TranspileError::UnregisteredExternal {ref name, ..}) => assert_eq!(&**name, "draw"),
+            x => panic!("Expected unregistered external, found {:?}", x),
+        }
     }
 
     #[test]
-    fn swizzle() {
-        let mut module = Module::new();
-        load("source/swizzle.dyon", &mut module).unwrap();
-        let code = generate_code_string(&module);
-        println!("{}", code);
-        assert_eq!(code, include_str!("../source/swizzle.rs"));
+    fn diff() {
+        let diff = unified_diff("a\nb\nc\n", "a\nc\nd\n");
+        assert_eq!(diff, "--- expected\n+++ actual\n@@ -1,3 +1,3 @@\n a\n-b\n c\n+d\n");
     }
 
     #[test]
-    fn return_() {
+    fn unsupported_expression() {
         let mut module = Module::new();
-        load("source/return.dyon", &mut module).unwrap();
-        let code = generate_code_string(&module);
-        println!("{}", code);
-        assert_eq!(code, include_str!("../source/return.rs"));
+        load("source/errors/array_fill.dyon", &mut module).unwrap();
+        match generate_code_string(&module) {
+            Err(TranspileError::UnsupportedExpression {ref kind, ref pos}) => {
+                assert_eq!(kind, "array fill");
+                assert_eq!(pos.line_col, Some((2, 10)));
+            }
+            x => panic!("Expected unsupported expression, found {:?}", x),
+        }
     }
 
     #[test]
-    fn mutate() {
+    fn type_conflict() {
         let mut module = Module::new();
-        load("source/mutate.dyon", &mut module).unwrap();
-        let code = generate_code_string(&module);
-        println!("{}", code);
-        assert_eq!(code, include_str!("../source/mutate.rs"));
+        load("source/errors/type_conflict.dyon", &mut module).unwrap();
+        let diagnostics = check_module(&module);
+        assert_eq!(diagnostics.len(), 1);
+        match diagnostics[0].error {
+            TranspileError::TypeConflict {ref expected, ref found, ref pos} => {
+                assert_eq!(expected, "f64");
+                assert_eq!(found, "str");
+                assert_eq!(pos.line_col, Some((3, 12)));
+            }
+            ref x => panic!("Expected type conflict, found {:?}", x),
+        }
     }
 
     #[test]
-    fn test() {
-        use std::fs::File;
-        use std::io::Write;
-
+    fn check() {
         let mut module = Module::new();
-        load("source/test.dyon", &mut module).unwrap();
-        let code = generate_code_string(&module);
-        println!("{}", code);
-
-        let run = true;
-        if run {
-            let mut file = File::create("source/test.rs").unwrap();
-            write!(file, "{}", code).unwrap();
-        } else {
-            assert_eq!(code, include_str!("../source/test.rs"));
+        load("source/errors/check.dyon", &mut module).unwrap();
+        let diagnostics = check_module(&module);
+        assert_eq!(diagnostics.len(), 2);
+        match diagnostics[0].error {
+            TranspileError::UnsupportedExpression {ref kind, ..} => assert_eq!(kind, "array fill"),
+            ref x => panic!("Expected unsupported expression, found {:?}", x),
+        }
+        match diagnostics[1].error {
+            TranspileError::UnsupportedIntrinsic {ref name, ref pos} => {
+                assert_eq!(&**name, "sleep");
+                assert_eq!(pos.line_col, Some((3, 5)));
+            }
+            ref x => panic!("Expected unsupported intrinsic, found {:?}", x),
         }
     }
 }
@@ -0,0 +1,80 @@
+//! Helps getting owned values from references.
+//!
+//! Function arguments are references in generated code,
+//! so `&_0` might be a reference to a reference.
+
+use std::sync::Arc;
+
+use dyon::{Error, Variable};
+use dyon::link::Link;
+
+pub trait Owned {
+    type Output;
+
+    fn owned(&self) -> Self::Output;
+}
+
+impl<'a, T: Owned + ?Sized> Owned for &'a T {
+    type Output = T::Output;
+
+    fn owned(&self) -> T::Output {(*self).owned()}
+}
+
+impl Owned for f64 {
+    type Output = f64;
+
+    fn owned(&self) -> f64 {*self}
+}
+
+impl Owned for bool {
+    type Output = bool;
+
+    fn owned(&self) -> bool {*self}
+}
+
+impl Owned for [f32; 4] {
+    type Output = [f32; 4];
+
+    fn owned(&self) -> [f32; 4] {*self}
+}
+
+impl Owned for Variable {
+    type Output = Variable;
+
+    fn owned(&self) -> Variable {self.clone()}
+}
+
+impl Owned for Link {
+    type Output = Link;
+
+    fn owned(&self) -> Link {self.clone()}
+}
+
+impl<T: Clone> Owned for Vec<T> {
+    type Output = Vec<T>;
+
+    fn owned(&self) -> Vec<T> {self.clone()}
+}
+
+impl<T: Clone> Owned for Option<T> {
+    type Output = Option<T>;
+
+    fn owned(&self) -> Option<T> {self.clone()}
+}
+
+impl<T: Clone> Owned for Result<T, Error> {
+    type Output = Result<T, Error>;
+
+    fn owned(&self) -> Result<T, Error> {self.clone()}
+}
+
+impl<T> Owned for Arc<T> {
+    type Output = Arc<T>;
+
+    fn owned(&self) -> Arc<T> {self.clone()}
+}
+
+/// Returns an owned value, e.g. when moving it to another thread.
+pub fn owned<T: Owned + ?Sized>(a: &T) -> T::Output {
+    a.owned()
+}
@@ -0,0 +1,60 @@
+//! Code generation settings.
+
+use Externals;
+
+/// Controls how Rust code is generated.
+#[derive(Clone, Debug)]
+pub struct CodeSettings {
+    /// Use variable names from the Dyon source instead of stack indices.
+    ///
+    /// Shadowed names get a suffix, e.g. `a_1`.
+    pub variable_names: bool,
+    /// Generate Rust structs for objects with statically known keys.
+    ///
+    /// Falls back to `HashMap` when a key or field type is not known,
+    /// or when the object is used in other ways than accessing fields
+    /// with literal keys or printing.
+    pub object_structs: bool,
+    /// Write crate attributes and `extern crate` lines.
+    ///
+    /// Disable this when the generated code is put inside a module.
+    pub preamble: bool,
+    /// Make generated functions and structs `pub`.
+    pub public: bool,
+    /// The path to the runtime crate, used in imports.
+    ///
+    /// When `preamble` is enabled, this must be a crate name.
+    pub runtime: String,
+    /// Rust functions to call for external functions in the Dyon module.
+    pub externals: Externals,
+}
+
+impl CodeSettings {
+    /// Creates new settings with default values.
+    pub fn new() -> CodeSettings {
+        CodeSettings {
+            variable_names: false,
+            object_structs: false,
+            preamble: true,
+            public: false,
+            runtime: String::from("dyon_to_rust"),
+            externals: Externals::new(),
+        }
+    }
+
+    /// Creates settings for generating a module that is used as a library.
+    ///
+    /// Functions are `pub` and there are no crate attributes or `extern crate` lines,
+    /// such that the code can be put inside a module of an existing crate.
+    pub fn library() -> CodeSettings {
+        CodeSettings {
+            preamble: false,
+            public: true,
+            ..CodeSettings::new()
+        }
+    }
+}
+
+impl Default for CodeSettings {
+    fn default() -> CodeSettings {CodeSettings::new()}
+}
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
-use dyon::Variable;
+use dyon::{Error, Object, Variable};
+use dyon::link::Link;
 
 pub trait ToVariable {
     fn to_variable(&self) -> Variable;
@@ -24,6 +25,18 @@ impl ToVariable for str {
     }
 }
 
+impl ToVariable for String {
+    fn to_variable(&self) -> Variable {
+        Variable::Text(Arc::new(self.clone()))
+    }
+}
+
+impl ToVariable for Arc<String> {
+    fn to_variable(&self) -> Variable {
+        Variable::Text(self.clone())
+    }
+}
+
 impl ToVariable for [f32; 4] {
     fn to_variable(&self) -> Variable {
         Variable::Vec4(*self)
@@ -36,12 +49,45 @@ impl<'a, T: ToVariable + ?Sized> ToVariable for &'a T {
     }
 }
 
+impl<'a, T: ToVariable + ?Sized> ToVariable for &'a mut T {
+    fn to_variable(&self) -> Variable {
+        (**self).to_variable()
+    }
+}
+
 impl<T: ToVariable> ToVariable for Vec<T> {
     fn to_variable(&self) -> Variable {
         Variable::Array(Arc::new(self.iter().map(|v| v.to_variable()).collect()))
     }
 }
 
+impl<T: ToVariable> ToVariable for Result<T, Error> {
+    fn to_variable(&self) -> Variable {
+        Variable::Result(match *self {
+            Ok(ref val) => Ok(Box::new(val.to_variable())),
+            Err(ref err) => Err(Box::new(err.clone())),
+        })
+    }
+}
+
+impl<T: ToVariable> ToVariable for Option<T> {
+    fn to_variable(&self) -> Variable {
+        Variable::Option(self.as_ref().map(|val| Box::new(val.to_variable())))
+    }
+}
+
+impl ToVariable for Arc<Object> {
+    fn to_variable(&self) -> Variable {
+        Variable::Object(self.clone())
+    }
+}
+
+impl ToVariable for Link {
+    fn to_variable(&self) -> Variable {
+        Variable::Link(Box::new(self.clone()))
+    }
+}
+
 impl ToVariable for Variable {
     fn to_variable(&self) -> Variable {
         self.clone()
@@ -52,3 +98,127 @@ impl ToVariable for Variable {
 pub fn to_variable<T: ToVariable>(a: &T) -> Variable {
     a.to_variable()
 }
+
+/// Converts a dynamic Dyon variable into a type.
+pub trait FromVariable: Sized {
+    fn from_variable(var: &Variable) -> Result<Self, String>;
+}
+
+/// Returns the name of the type of a variable, used in error messages.
+fn type_name(var: &Variable) -> &'static str {
+    match *var {
+        Variable::F64(_, _) => "f64",
+        Variable::Bool(_, _) => "bool",
+        Variable::Vec4(_) => "vec4",
+        Variable::Text(_) => "str",
+        Variable::Array(_) => "array",
+        Variable::Object(_) => "object",
+        Variable::Link(_) => "link",
+        Variable::Option(_) => "option",
+        Variable::Result(_) => "result",
+        _ => "other",
+    }
+}
+
+fn expected(ty: &str, var: &Variable) -> String {
+    format!("Expected `{}`, found `{}`", ty, type_name(var))
+}
+
+impl FromVariable for f64 {
+    fn from_variable(var: &Variable) -> Result<f64, String> {
+        match *var {
+            Variable::F64(val, _) => Ok(val),
+            ref x => Err(expected("f64", x)),
+        }
+    }
+}
+
+impl FromVariable for bool {
+    fn from_variable(var: &Variable) -> Result<bool, String> {
+        match *var {
+            Variable::Bool(val, _) => Ok(val),
+            ref x => Err(expected("bool", x)),
+        }
+    }
+}
+
+impl FromVariable for [f32; 4] {
+    fn from_variable(var: &Variable) -> Result<[f32; 4], String> {
+        match *var {
+            Variable::Vec4(val) => Ok(val),
+            ref x => Err(expected("vec4", x)),
+        }
+    }
+}
+
+impl FromVariable for Arc<String> {
+    fn from_variable(var: &Variable) -> Result<Arc<String>, String> {
+        match *var {
+            Variable::Text(ref val) => Ok(val.clone()),
+            ref x => Err(expected("str", x)),
+        }
+    }
+}
+
+impl<T: FromVariable> FromVariable for Vec<T> {
+    fn from_variable(var: &Variable) -> Result<Vec<T>, String> {
+        match *var {
+            Variable::Array(ref arr) => arr.iter().map(T::from_variable).collect(),
+            ref x => Err(expected("array", x)),
+        }
+    }
+}
+
+impl<T: FromVariable> FromVariable for Option<T> {
+    fn from_variable(var: &Variable) -> Result<Option<T>, String> {
+        match *var {
+            Variable::Option(None) => Ok(None),
+            Variable::Option(Some(ref val)) => Ok(Some(T::from_variable(val)?)),
+            ref x => Err(expected("option", x)),
+        }
+    }
+}
+
+impl<T: FromVariable> FromVariable for Result<T, Error> {
+    fn from_variable(var: &Variable) -> Result<Result<T, Error>, String> {
+        match *var {
+            Variable::Result(Ok(ref val)) => Ok(Ok(T::from_variable(val)?)),
+            Variable::Result(Err(ref err)) => Ok(Err((**err).clone())),
+            ref x => Err(expected("result", x)),
+        }
+    }
+}
+
+impl FromVariable for Arc<Object> {
+    fn from_variable(var: &Variable) -> Result<Arc<Object>, String> {
+        match *var {
+            Variable::Object(ref obj) => Ok(obj.clone()),
+            ref x => Err(expected("object", x)),
+        }
+    }
+}
+
+impl FromVariable for Link {
+    fn from_variable(var: &Variable) -> Result<Link, String> {
+        match *var {
+            Variable::Link(ref link) => Ok((**link).clone()),
+            ref x => Err(expected("link", x)),
+        }
+    }
+}
+
+impl FromVariable for Variable {
+    fn from_variable(var: &Variable) -> Result<Variable, String> {
+        Ok(var.clone())
+    }
+}
+
+/// Helps converting a dynamic Dyon variable into a type.
+///
+/// Panics when the variable has another type, like Dyon reports a runtime error.
+pub fn from_variable<T: FromVariable>(var: &Variable) -> T {
+    match T::from_variable(var) {
+        Ok(val) => val,
+        Err(err) => panic!("{}", err),
+    }
+}
@@ -0,0 +1,99 @@
+//! Walks the Dyon AST.
+
+use dyon::ast::*;
+
+/// Calls a function for an expression and all expressions inside it.
+///
+/// Children of expressions that are not supported by the transpiler are not visited.
+pub fn walk_expression<F: FnMut(&Expression)>(exp: &Expression, f: &mut F) {
+    use dyon::ast::Expression as E;
+
+    f(exp);
+    match *exp {
+        E::Item(ref item) => walk_item(item, f),
+        E::Call(ref call) => walk_expressions(&call.args, f),
+        E::Go(ref go) => walk_expressions(&go.call.args, f),
+        E::CallClosure(ref call_closure) => {
+            walk_item(&call_closure.item, f);
+            walk_expressions(&call_closure.args, f);
+        }
+        E::Closure(ref closure) => walk_expression(&closure.expr, f),
+        E::BinOp(ref binop) => {
+            walk_expression(&binop.left, f);
+            walk_expression(&binop.right, f);
+        }
+        E::Compare(ref compare) => {
+            walk_expression(&compare.left, f);
+            walk_expression(&compare.right, f);
+        }
+        E::Assign(ref assign) => {
+            walk_expression(&assign.left, f);
+            walk_expression(&assign.right, f);
+        }
+        E::UnOp(ref unop) => walk_expression(&unop.expr, f),
+        E::Return(ref e) | E::Try(ref e) => walk_expression(e, f),
+        E::Swizzle(ref swizzle) => walk_expression(&swizzle.expr, f),
+        E::Block(ref block) => walk_block(block, f),
+        E::Array(ref array) => walk_expressions(&array.items, f),
+        E::Vec4(ref vec4) => walk_expressions(&vec4.args, f),
+        E::Link(ref link) => walk_expressions(&link.items, f),
+        E::Object(ref obj) => {
+            for &(_, ref e) in &obj.key_values {
+                walk_expression(e, f);
+            }
+        }
+        E::If(ref if_expr) => {
+            walk_expression(&if_expr.cond, f);
+            walk_block(&if_expr.true_block, f);
+            walk_expressions(&if_expr.else_if_conds, f);
+            for block in &if_expr.else_if_blocks {
+                walk_block(block, f);
+            }
+            if let Some(ref block) = if_expr.else_block {
+                walk_block(block, f);
+            }
+        }
+        E::Loop(ref loop_expr) => walk_block(&loop_expr.block, f),
+        E::For(ref for_expr) => {
+            walk_expression(&for_expr.init, f);
+            walk_expression(&for_expr.cond, f);
+            walk_expression(&for_expr.step, f);
+            walk_block(&for_expr.block, f);
+        }
+        E::ForN(ref for_n) | E::Sum(ref for_n) | E::Prod(ref for_n) |
+        E::Min(ref for_n) | E::Max(ref for_n) | E::Sift(ref for_n) |
+        E::Any(ref for_n) | E::All(ref for_n) => {
+            if let Some(ref start) = for_n.start {
+                walk_expression(start, f);
+            }
+            walk_expression(&for_n.end, f);
+            walk_block(&for_n.block, f);
+        }
+        E::ForIn(ref for_in) | E::SumIn(ref for_in) | E::ProdIn(ref for_in) |
+        E::MinIn(ref for_in) | E::MaxIn(ref for_in) | E::SiftIn(ref for_in) |
+        E::AnyIn(ref for_in) | E::AllIn(ref for_in) => {
+            walk_expression(&for_in.iter, f);
+            walk_block(&for_in.block, f);
+        }
+        _ => {}
+    }
+}
+
+/// Calls a function for all expressions in a block.
+pub fn walk_block<F: FnMut(&Expression)>(block: &Block, f: &mut F) {
+    walk_expressions(&block.expressions, f);
+}
+
+fn walk_expressions<F: FnMut(&Expression)>(exps: &[Expression], f: &mut F) {
+    for exp in exps {
+        walk_expression(exp, f);
+    }
+}
+
+fn walk_item<F: FnMut(&Expression)>(item: &Item, f: &mut F) {
+    for id in &item.ids {
+        if let Id::Expression(ref exp) = *id {
+            walk_expression(exp, f);
+        }
+    }
+}
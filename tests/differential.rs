@@ -0,0 +1,125 @@
+//! Runs Dyon scripts and the transpiled Rust code side by side.
+//!
+//! For each "source/*.dyon", the script is executed by the Dyon interpreter
+//! (using the "test" example) and the golden ".rs" file is compiled against this crate.
+//! The output of both programs must be identical.
+//!
+//! This test is slow, so it is ignored by default.
+//! Run it with `cargo test --test differential -- --ignored`.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// Scripts that are not checked.
+const SKIP: &[&str] = &[
+    // Scratch file for working on the transpiler.
+    "test",
+];
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+/// Builds the library of this crate and returns its path.
+///
+/// Cargo reports the built files in JSON messages, one per line.
+fn build_runtime() -> Result<PathBuf, String> {
+    let output = Command::new(env!("CARGO"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(&["build", "--lib", "--message-format=json"])
+        .output()
+        .map_err(|err| format!("Could not run cargo: {}", err))?;
+    if !output.status.success() {
+        return Err(format!("cargo build failed:\n{}", String::from_utf8_lossy(&output.stderr)));
+    }
+    stdout(&output).lines()
+        .filter(|line| {
+            line.contains("\"reason\":\"compiler-artifact\"") &&
+            line.contains("\"name\":\"dyon_to_rust\"")
+        })
+        .flat_map(|line| line.split('"').map(String::from).collect::<Vec<String>>())
+        .find(|s| s.ends_with(".rlib"))
+        .map(PathBuf::from)
+        .ok_or_else(|| String::from("Could not find the dyon_to_rust library in the cargo output"))
+}
+
+/// Runs the script in the Dyon interpreter.
+fn run_dyon(file: &Path) -> Result<String, String> {
+    let output = Command::new(env!("CARGO"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(&["run", "--quiet", "--example", "test", "--"])
+        .arg(file)
+        .output()
+        .map_err(|err| format!("Could not run cargo: {}", err))?;
+    if !output.status.success() {
+        return Err(format!("Dyon failed:\n{}", String::from_utf8_lossy(&output.stderr)));
+    }
+    Ok(stdout(&output))
+}
+
+/// Compiles the Rust code against the runtime library and runs it.
+fn run_rust(file: &Path, rlib: &Path, out_dir: &Path) -> Result<String, String> {
+    // The dependencies of the runtime are next to it.
+    let deps = rlib.parent().unwrap();
+    let exe = out_dir.join(file.file_stem().unwrap());
+    let output = Command::new("rustc")
+        .arg(file)
+        .arg("-o").arg(&exe)
+        .arg("-L").arg(format!("dependency={}", deps.display()))
+        .arg("--extern").arg(format!("dyon_to_rust={}", rlib.display()))
+        .output()
+        .map_err(|err| format!("Could not run rustc: {}", err))?;
+    if !output.status.success() {
+        return Err(format!("rustc failed:\n{}", String::from_utf8_lossy(&output.stderr)));
+    }
+
+    let output = Command::new(&exe)
+        .output()
+        .map_err(|err| format!("Could not run `{}`: {}", exe.display(), err))?;
+    if !output.status.success() {
+        return Err(format!("Rust program failed:\n{}", String::from_utf8_lossy(&output.stderr)));
+    }
+    Ok(stdout(&output))
+}
+
+#[test]
+#[ignore]
+fn differential() {
+    let rlib = build_runtime().unwrap();
+    let source = Path::new(env!("CARGO_MANIFEST_DIR")).join("source");
+    let out_dir = env::temp_dir().join("dyon_to_rust_differential");
+    fs::create_dir_all(&out_dir).unwrap();
+
+    let mut files: Vec<PathBuf> = fs::read_dir(&source).unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().map(|ext| ext == "dyon").unwrap_or(false))
+        .filter(|path| {
+            let name = path.file_stem().unwrap().to_str().unwrap();
+            !SKIP.iter().any(|&skip| skip == name)
+        })
+        .collect();
+    files.sort();
+
+    let mut failures = vec![];
+    for dyon_file in &files {
+        let rust_file = dyon_file.with_extension("rs");
+        let res = run_dyon(dyon_file).and_then(|expected| {
+            let actual = run_rust(&rust_file, &rlib, &out_dir)?;
+            if expected == actual {
+                Ok(())
+            } else {
+                Err(format!("Output differs\n--- dyon\n{}\n--- rust\n{}", expected, actual))
+            }
+        });
+        if let Err(err) = res {
+            failures.push(format!("{}: {}", dyon_file.display(), err));
+        }
+    }
+
+    if !failures.is_empty() {
+        panic!("{} of {} scripts failed:\n\n{}",
+            failures.len(), files.len(), failures.join("\n\n"));
+    }
+}